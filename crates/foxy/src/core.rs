@@ -1,14 +1,26 @@
+use std::any::Any;
+
 use foxy_renderer::error::RendererError;
 use foxy_utils::thread::error::ThreadError;
 use thiserror::Error;
 
 pub mod builder;
-pub mod foxy_state;
+pub mod clipboard;
+pub mod drag_drop;
+pub mod ecs;
 pub mod event;
+pub mod event_proxy;
 pub mod foxy_loop;
+pub mod foxy_state;
+pub mod headless;
 pub mod input;
 pub mod message;
+pub mod plugin;
+pub mod resources;
 pub mod runnable;
+pub mod scene;
+pub mod schedule;
+pub mod window_command;
 
 pub type FoxyResult<T> = Result<T, FoxyError>;
 
@@ -34,6 +46,24 @@ pub enum FoxyError {
   HandleError(#[from] winit::raw_window_handle::HandleError),
   #[error("{0}")]
   CursorIconParseError(#[from] winit::window::CursorIconParseError),
+  #[error("{0}")]
+  ClipboardError(#[from] arboard::Error),
+  /// The game thread panicked. `message` is the text the panic was raised
+  /// with, when it had any.
+  #[error("game thread panicked: {message}")]
+  Panic { message: String },
+}
+
+impl FoxyError {
+  pub(crate) fn from_panic(payload: Box<dyn Any + Send>) -> Self {
+    let message = match (payload.downcast_ref::<&str>(), payload.downcast_ref::<String>()) {
+      (Some(message), _) => message.to_string(),
+      (_, Some(message)) => message.clone(),
+      _ => "unknown panic payload".to_string(),
+    };
+
+    FoxyError::Panic { message }
+  }
 }
 
 #[macro_export]
@@ -55,3 +85,23 @@ macro_rules! foxy_error {
 //     Err($crate::core::FoxyError::Error(format!($($arg)*)))
 //   }}
 // }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn errors_can_cross_threads() {
+    fn assert_send_sync<T: Send + Sync + 'static>() {}
+    assert_send_sync::<FoxyError>();
+  }
+
+  #[test]
+  fn panic_message_is_kept() {
+    let error = FoxyError::from_panic(Box::new("boom"));
+    assert!(matches!(error, FoxyError::Panic { ref message } if message == "boom"));
+
+    let error = FoxyError::from_panic(Box::new(String::from("bang")));
+    assert_eq!(error.to_string(), "game thread panicked: bang");
+  }
+}
@@ -1,14 +1,61 @@
 use foxy_utils::time::TimeCreateInfo;
 use winit::dpi::{LogicalSize, Size};
 
-use crate::window::WindowCreateInfo;
+use super::{
+  ecs::WorldSystem,
+  event::FoxyEvent,
+  foxy_state::Foxy,
+  plugin::Plugin,
+  schedule::{Phase, Schedule},
+  FoxyResult,
+};
+#[cfg(target_os = "linux")]
+use crate::window::{AppId, LinuxBackend};
+use crate::{
+  core::input::{action::ActionMap, gamepad::Gamepads},
+  window::WindowCreateInfo,
+};
 
+/// How often the main thread redraws the window.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 #[allow(unused)]
 pub enum Polling {
+  /// Redraw as fast as possible.
   Poll,
+  /// Redraw only once something happens, e.g. input.
   #[default]
   Wait,
+  /// Both threads sleep until there is something new to show: input, a
+  /// [user event](super::event_proxy::EventProxy),
+  /// [`State::request_redraw`](super::foxy_state::State::request_redraw) or a
+  /// repaint egui asked for. For tools and editors that should idle at close
+  /// to zero CPU and GPU.
+  ///
+  /// Gamepads and long press gestures are only polled while awake, and fixed
+  /// updates don't make up for time spent asleep.
+  Reactive,
+  /// Redraw at most `fps` times per second, sleeping in between.
+  ///
+  /// This only paces rendering. The game thread keeps updating as fast as it
+  /// can unless it is capped with
+  /// [`with_update_limit`](FoxyCreateInfo::with_update_limit) as well.
+  Limited { fps: u32 },
+}
+
+/// What the game does while its window is hidden: minimized, fully covered
+/// by other windows, or the whole app suspended by the OS. Drawing always
+/// stops while hidden.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LifecyclePolicy {
+  /// Keep updating as usual.
+  #[default]
+  KeepUpdating,
+  /// Stop updating until shown again. The game clock keeps running, so fixed
+  /// updates catch up afterwards.
+  StopUpdating,
+  /// Stop updating and pause the game clock, as if no time passed while
+  /// hidden.
+  Pause,
 }
 
 #[derive(Debug, Default)]
@@ -23,7 +70,14 @@ pub struct FoxyCreateInfo {
   pub time: TimeCreateInfo,
   pub window: WindowCreateInfo,
   pub polling_strategy: Polling,
+  pub vsync: bool,
+  /// Most game loop iterations per second. `None` runs uncapped.
+  pub update_limit: Option<u32>,
+  pub lifecycle: LifecyclePolicy,
   pub debug_info: DebugInfo,
+  pub actions: ActionMap,
+  pub gamepads: Gamepads,
+  pub schedule: Schedule,
 }
 
 impl FoxyCreateInfo {
@@ -45,16 +99,95 @@ impl FoxyCreateInfo {
     self
   }
 
+  #[cfg(target_os = "linux")]
+  pub fn with_app_id(mut self, app_id: AppId) -> Self {
+    self.window.app_id = Some(app_id);
+    self
+  }
+
+  #[cfg(target_os = "linux")]
+  pub fn with_linux_backend(mut self, backend: LinuxBackend) -> Self {
+    self.window.linux_backend = backend;
+    self
+  }
+
   pub fn with_polling(mut self, polling_strategy: Polling) -> Self {
     self.polling_strategy = polling_strategy;
     self
   }
 
+  /// Waits for the display's refresh before presenting, which avoids tearing
+  /// and caps the frame rate at the refresh rate. Off by default.
+  pub fn with_vsync(mut self, vsync: bool) -> Self {
+    self.vsync = vsync;
+    self
+  }
+
+  /// Caps how many times per second the game thread runs
+  /// [`Runnable::update`](super::runnable::Runnable::update). Fixed updates
+  /// still run at the tick rate, catching up over several ticks per frame when
+  /// needed.
+  pub fn with_update_limit(mut self, updates_per_second: u32) -> Self {
+    self.update_limit = Some(updates_per_second);
+    self
+  }
+
+  pub fn with_lifecycle(mut self, lifecycle: LifecyclePolicy) -> Self {
+    self.lifecycle = lifecycle;
+    self
+  }
+
   pub fn with_debug_info(mut self, debug_info: DebugInfo) -> Self {
     self.debug_info = debug_info;
     self
   }
 
+  /// Initial input bindings, e.g. loaded from a player's config file.
+  pub fn with_actions(mut self, actions: ActionMap) -> Self {
+    self.actions = actions;
+    self
+  }
+
+  /// Where gamepad input comes from. Pass a
+  /// [`VirtualGamepads`](crate::core::input::gamepad::VirtualGamepads) to drive
+  /// gamepads from tests.
+  pub fn with_gamepads(mut self, gamepads: impl Into<Gamepads>) -> Self {
+    self.gamepads = gamepads.into();
+    self
+  }
+
+  /// Runs `system` every frame in `phase`, after the app's own hooks for that
+  /// phase and after any systems added before it.
+  pub fn with_system(
+    mut self,
+    phase: Phase,
+    system: impl FnMut(&Foxy, &[FoxyEvent]) -> FoxyResult<()> + Send + 'static,
+  ) -> Self {
+    self.schedule.add_system(phase, system);
+    self
+  }
+
+  /// Runs `system` every frame in `phase`, in order with the systems added
+  /// through [`with_system`](Self::with_system).
+  pub fn with_world_system(mut self, phase: Phase, system: WorldSystem) -> Self {
+    self.schedule.add_world_system(phase, system);
+    self
+  }
+
+  /// Lets world systems that don't conflict run in parallel. See
+  /// [`Schedule::set_parallel`].
+  pub fn with_parallel_systems(mut self, parallel: bool) -> Self {
+    self.schedule.set_parallel(parallel);
+    self
+  }
+
+  /// Adds a plugin. Plugins are built, started and run in the order they were
+  /// added.
+  pub fn with_plugin(mut self, plugin: impl Plugin) -> Self {
+    self.schedule.add_plugin(plugin);
+    self
+  }
+
   pub fn with_time(mut self, time: TimeCreateInfo) -> Self {
     self.time = time;
     self
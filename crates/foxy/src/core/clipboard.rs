@@ -0,0 +1,179 @@
+use std::{
+  borrow::Cow,
+  sync::{Arc, Mutex, MutexGuard},
+};
+
+use super::FoxyResult;
+use crate::foxy_error;
+
+/// An image on the clipboard, four bytes per pixel in RGBA order with rows
+/// top to bottom. The same layout
+/// [`DiffuseTexture::from_rgba`](foxy_renderer::renderer::texture::DiffuseTexture::from_rgba)
+/// expects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClipboardImage {
+  pub width: u32,
+  pub height: u32,
+  pub rgba: Vec<u8>,
+}
+
+impl ClipboardImage {
+  pub fn new(width: u32, height: u32, rgba: Vec<u8>) -> FoxyResult<Self> {
+    if rgba.len() != width as usize * height as usize * 4 {
+      return Err(foxy_error!("{} bytes of rgba don't make a {width}x{height} image", rgba.len()));
+    }
+
+    Ok(Self { width, height, rgba })
+  }
+}
+
+enum Backend {
+  /// Connected on first use, so apps that never touch the clipboard don't pay
+  /// for it.
+  Native(Option<arboard::Clipboard>),
+  /// Private to the app, for headless runs where there may be no system
+  /// clipboard to talk to.
+  Memory {
+    text: Option<String>,
+    image: Option<ClipboardImage>,
+  },
+}
+
+/// A handle to the system clipboard, from
+/// [`State::clipboard`](super::foxy_state::State::clipboard). Clones share
+/// the same connection.
+///
+/// Reading fails when the clipboard is empty or holds something else, e.g.
+/// asking for text while it holds an image.
+///
+/// On Linux, what the app copies is only available to other apps while it
+/// is running.
+#[derive(Clone)]
+pub struct Clipboard {
+  backend: Arc<Mutex<Backend>>,
+}
+
+impl std::fmt::Debug for Clipboard {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("Clipboard").finish_non_exhaustive()
+  }
+}
+
+impl Clipboard {
+  pub(crate) fn native() -> Self {
+    Self {
+      backend: Arc::new(Mutex::new(Backend::Native(None))),
+    }
+  }
+
+  pub(crate) fn memory() -> Self {
+    Self {
+      backend: Arc::new(Mutex::new(Backend::Memory {
+        text: None,
+        image: None,
+      })),
+    }
+  }
+
+  pub fn text(&self) -> FoxyResult<String> {
+    match &mut *self.backend()? {
+      Backend::Native(clipboard) => Ok(open(clipboard)?.get_text()?),
+      Backend::Memory { text, .. } => text.clone().ok_or_else(|| foxy_error!("the clipboard holds no text")),
+    }
+  }
+
+  pub fn set_text(&self, value: impl Into<String>) -> FoxyResult<()> {
+    match &mut *self.backend()? {
+      Backend::Native(clipboard) => open(clipboard)?.set_text(value.into())?,
+      Backend::Memory { text, image } => {
+        *text = Some(value.into());
+        *image = None;
+      }
+    }
+
+    Ok(())
+  }
+
+  pub fn image(&self) -> FoxyResult<ClipboardImage> {
+    match &mut *self.backend()? {
+      Backend::Native(clipboard) => {
+        let image = open(clipboard)?.get_image()?;
+        ClipboardImage::new(image.width as u32, image.height as u32, image.bytes.into_owned())
+      }
+      Backend::Memory { image, .. } => image.clone().ok_or_else(|| foxy_error!("the clipboard holds no image")),
+    }
+  }
+
+  pub fn set_image(&self, value: &ClipboardImage) -> FoxyResult<()> {
+    match &mut *self.backend()? {
+      Backend::Native(clipboard) => open(clipboard)?.set_image(arboard::ImageData {
+        width: value.width as usize,
+        height: value.height as usize,
+        bytes: Cow::Borrowed(&value.rgba),
+      })?,
+      Backend::Memory { text, image } => {
+        *image = Some(value.clone());
+        *text = None;
+      }
+    }
+
+    Ok(())
+  }
+
+  pub fn clear(&self) -> FoxyResult<()> {
+    match &mut *self.backend()? {
+      Backend::Native(clipboard) => open(clipboard)?.clear()?,
+      Backend::Memory { text, image } => {
+        *text = None;
+        *image = None;
+      }
+    }
+
+    Ok(())
+  }
+
+  fn backend(&self) -> FoxyResult<MutexGuard<'_, Backend>> {
+    self
+      .backend
+      .lock()
+      .map_err(|_| foxy_error!("clipboard poisoned by a panic"))
+  }
+}
+
+/// Connects to the system clipboard if this is the first use.
+fn open(slot: &mut Option<arboard::Clipboard>) -> FoxyResult<&mut arboard::Clipboard> {
+  let clipboard = match slot.take() {
+    Some(clipboard) => clipboard,
+    None => arboard::Clipboard::new()?,
+  };
+
+  Ok(slot.insert(clipboard))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn images_must_match_their_size() {
+    assert!(ClipboardImage::new(2, 1, vec![0; 8]).is_ok());
+    assert!(ClipboardImage::new(2, 2, vec![0; 8]).is_err());
+  }
+
+  #[test]
+  fn memory_holds_one_kind_at_a_time() {
+    let clipboard = Clipboard::memory();
+    assert!(clipboard.text().is_err());
+
+    clipboard.set_text("fox").unwrap();
+    assert_eq!(clipboard.text().unwrap(), "fox");
+
+    let image = ClipboardImage::new(1, 1, vec![255; 4]).unwrap();
+    clipboard.set_image(&image).unwrap();
+    assert_eq!(clipboard.image().unwrap(), image);
+    assert!(clipboard.text().is_err());
+
+    clipboard.clear().unwrap();
+    assert!(clipboard.image().is_err());
+  }
+}
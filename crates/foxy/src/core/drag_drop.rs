@@ -0,0 +1,90 @@
+use std::path::{Path, PathBuf};
+
+use winit::dpi::PhysicalPosition;
+
+/// Files dragged onto the window. When several files are dragged at once,
+/// `HoverEnter` and `Drop` are sent once per file.
+///
+/// `position` is the last known cursor position in physical pixels. Some
+/// platforms don't report cursor movement during a drag, so it can be stale
+/// or `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DragDropEvent {
+  HoverEnter {
+    path: PathBuf,
+    position: Option<PhysicalPosition<f64>>,
+  },
+  /// The drag left the window or was cancelled.
+  HoverLeave,
+  Drop {
+    path: PathBuf,
+    position: Option<PhysicalPosition<f64>>,
+  },
+}
+
+impl DragDropEvent {
+  pub fn path(&self) -> Option<&Path> {
+    match self {
+      DragDropEvent::HoverEnter { path, .. } | DragDropEvent::Drop { path, .. } => Some(path),
+      DragDropEvent::HoverLeave => None,
+    }
+  }
+
+  /// What the dragged file looks like, judging by its extension.
+  pub fn kind(&self) -> Option<AssetKind> {
+    self.path().map(AssetKind::from_path)
+  }
+}
+
+/// A rough guess at what a file contains, for deciding how to import it.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum AssetKind {
+  Image,
+  Model,
+  Scene,
+  Config,
+  Other,
+}
+
+impl AssetKind {
+  pub fn from_path(path: &Path) -> Self {
+    path
+      .extension()
+      .and_then(|extension| extension.to_str())
+      .map_or(AssetKind::Other, Self::from_extension)
+  }
+
+  /// Case-insensitive, without the leading dot.
+  pub fn from_extension(extension: &str) -> Self {
+    match extension.to_ascii_lowercase().as_str() {
+      "png" | "jpg" | "jpeg" | "bmp" | "tga" | "gif" | "webp" | "hdr" | "exr" | "dds" | "ktx2" | "qoi" => {
+        AssetKind::Image
+      }
+      "gltf" | "glb" | "obj" | "fbx" | "dae" | "stl" | "ply" | "blend" => AssetKind::Model,
+      "scene" | "scn" | "level" | "map" | "usd" | "usda" | "usdc" | "usdz" => AssetKind::Scene,
+      "toml" | "ron" | "json" | "yaml" | "yml" | "ini" | "cfg" => AssetKind::Config,
+      _ => AssetKind::Other,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn extensions_ignore_case() {
+    assert_eq!(AssetKind::from_extension("PNG"), AssetKind::Image);
+    assert_eq!(AssetKind::from_extension("Glb"), AssetKind::Model);
+    assert_eq!(AssetKind::from_path(Path::new("levels/Intro.SCN")), AssetKind::Scene);
+  }
+
+  #[test]
+  fn missing_or_unknown_extensions_are_other() {
+    assert_eq!(AssetKind::from_path(Path::new("README")), AssetKind::Other);
+    assert_eq!(AssetKind::from_path(Path::new(".png")), AssetKind::Other);
+    assert_eq!(AssetKind::from_extension(""), AssetKind::Other);
+    assert_eq!(AssetKind::from_extension("exe"), AssetKind::Other);
+    assert_eq!(AssetKind::from_path(Path::new("notes.txt")), AssetKind::Other);
+  }
+}
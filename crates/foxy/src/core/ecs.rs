@@ -0,0 +1,46 @@
+//! A small sparse-set entity component system.
+//!
+//! The [`World`] lives on [`Foxy`](super::foxy_state::Foxy) and is reached
+//! with [`Foxy::world`](super::foxy_state::Foxy::world). Each component type
+//! is stored in its own set behind its own lock, so queries over different
+//! components never wait on each other.
+//!
+//! ```ignore
+//! let player = foxy.world().spawn((Position::default(), Velocity { x: 1.0, y: 0.0 }));
+//!
+//! let movement = WorldSystem::new("movement", |ctx| {
+//!   let delta = ctx.foxy.read().time().delta_secs() as f32;
+//!   ctx.query::<(&Velocity, &mut Position)>()?.for_each(|(velocity, mut position)| {
+//!     position.x += velocity.x * delta;
+//!     position.y += velocity.y * delta;
+//!   });
+//!   Ok(())
+//! })
+//! .reads::<Velocity>()
+//! .writes::<Position>();
+//!
+//! let create_info = FoxyCreateInfo::default().with_world_system(Phase::Update, movement);
+//! ```
+
+pub mod bundle;
+pub mod entity;
+pub mod event;
+pub mod query;
+pub mod storage;
+pub mod system;
+pub mod world;
+
+pub use self::{
+  bundle::Bundle,
+  entity::Entity,
+  query::{Access, Added, Changed, Query, QueryFilter, With, Without, WorldQuery},
+  storage::{Mut, Ref, RefMut},
+  system::{SystemContext, WorldSystem},
+  world::World,
+};
+
+/// Anything that can be attached to an [`Entity`]. Implemented for every type
+/// that can be shared between threads.
+pub trait Component: Send + Sync + 'static {}
+
+impl<T: Send + Sync + 'static> Component for T {}
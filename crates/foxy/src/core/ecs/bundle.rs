@@ -0,0 +1,30 @@
+use super::{entity::Entity, world::World, Component};
+
+/// Components to spawn an entity with, as a tuple of up to eight, e.g.
+/// `(Position::default(), Velocity::default())`. `()` spawns an empty entity.
+pub trait Bundle: Send + 'static {
+  fn insert_into(self, world: &World, entity: Entity);
+}
+
+macro_rules! impl_bundle {
+  ($($name:ident),*) => {
+    impl<$($name: Component),*> Bundle for ($($name,)*) {
+      #[allow(non_snake_case, unused_variables)]
+      fn insert_into(self, world: &World, entity: Entity) {
+        let ($($name,)*) = self;
+        // Only fails if another thread already despawned the entity
+        $(let _ = world.insert(entity, $name);)*
+      }
+    }
+  };
+}
+
+impl_bundle!();
+impl_bundle!(A);
+impl_bundle!(A, B);
+impl_bundle!(A, B, C);
+impl_bundle!(A, B, C, D);
+impl_bundle!(A, B, C, D, E);
+impl_bundle!(A, B, C, D, E, F);
+impl_bundle!(A, B, C, D, E, F, G);
+impl_bundle!(A, B, C, D, E, F, G, H);
@@ -0,0 +1,107 @@
+/// A handle to a thing in the [`World`](super::World). Handles stay unique
+/// after despawning: a reused slot gets a new generation, so old handles
+/// simply stop matching.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Entity {
+  index: u32,
+  generation: u32,
+}
+
+impl Entity {
+  pub fn index(&self) -> u32 {
+    self.index
+  }
+
+  pub fn generation(&self) -> u32 {
+    self.generation
+  }
+}
+
+/// Hands out entity slots and recycles the ones freed by despawning.
+#[derive(Debug, Default)]
+pub(crate) struct Entities {
+  generations: Vec<u32>,
+  alive: Vec<bool>,
+  free: Vec<u32>,
+  len: usize,
+}
+
+impl Entities {
+  pub(crate) fn alloc(&mut self) -> Entity {
+    self.len += 1;
+    let index = match self.free.pop() {
+      Some(index) => index,
+      None => {
+        self.generations.push(0);
+        self.alive.push(false);
+        (self.alive.len() - 1) as u32
+      }
+    };
+
+    self.alive[index as usize] = true;
+    Entity {
+      index,
+      generation: self.generations[index as usize],
+    }
+  }
+
+  /// Returns whether `entity` was alive.
+  pub(crate) fn free(&mut self, entity: Entity) -> bool {
+    if !self.is_alive(entity) {
+      return false;
+    }
+
+    let index = entity.index as usize;
+    self.alive[index] = false;
+    self.generations[index] = self.generations[index].wrapping_add(1);
+    self.free.push(entity.index);
+    self.len -= 1;
+    true
+  }
+
+  pub(crate) fn is_alive(&self, entity: Entity) -> bool {
+    let index = entity.index as usize;
+    self.alive.get(index).copied().unwrap_or(false) && self.generations[index] == entity.generation
+  }
+
+  pub(crate) fn len(&self) -> usize {
+    self.len
+  }
+
+  pub(crate) fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+    self
+      .alive
+      .iter()
+      .zip(&self.generations)
+      .enumerate()
+      .filter(|(_, (alive, _))| **alive)
+      .map(|(index, (_, generation))| Entity {
+        index: index as u32,
+        generation: *generation,
+      })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn freed_slots_are_reused_with_a_new_generation() {
+    let mut entities = Entities::default();
+    let first = entities.alloc();
+    let second = entities.alloc();
+    assert_eq!(entities.len(), 2);
+
+    assert!(entities.free(first));
+    assert!(!entities.free(first));
+    assert!(!entities.is_alive(first));
+
+    let reused = entities.alloc();
+    assert_eq!(reused.index(), first.index());
+    assert_eq!(reused.generation(), first.generation() + 1);
+    assert!(entities.is_alive(reused));
+    assert!(!entities.is_alive(first));
+    assert_eq!(entities.iter().collect::<Vec<_>>(), vec![reused, second]);
+  }
+}
@@ -0,0 +1,107 @@
+use std::{any::Any, sync::Arc};
+
+use parking_lot::Mutex;
+
+/// Events of one type, kept for the frame they were sent in and the one
+/// after, so every system gets a chance to see them wherever it runs.
+pub(crate) struct EventBuffer<E> {
+  events: Vec<(u64, E)>,
+  next_id: u64,
+  frame_start: u64,
+}
+
+impl<E> Default for EventBuffer<E> {
+  fn default() -> Self {
+    Self {
+      events: Vec::new(),
+      next_id: 0,
+      frame_start: 0,
+    }
+  }
+}
+
+impl<E: Clone> EventBuffer<E> {
+  pub(crate) fn send(&mut self, event: E) {
+    self.events.push((self.next_id, event));
+    self.next_id += 1;
+  }
+
+  /// Events from `cursor` on, and the cursor to continue from next time.
+  pub(crate) fn read_from(&self, cursor: u64) -> (Vec<E>, u64) {
+    let events = self
+      .events
+      .iter()
+      .filter(|(id, _)| *id >= cursor)
+      .map(|(_, event)| event.clone())
+      .collect();
+    (events, self.next_id)
+  }
+}
+
+impl<E> EventBuffer<E> {
+  fn clear(&mut self) {
+    self.events.clear();
+  }
+
+  fn begin_frame(&mut self) {
+    let frame_start = self.frame_start;
+    self.events.retain(|(id, _)| *id >= frame_start);
+    self.frame_start = self.next_id;
+  }
+}
+
+/// An event buffer with its type erased, for aging events each frame.
+pub(crate) trait ErasedEvents: Send + Sync {
+  fn begin_frame(&self);
+
+  fn clear(&self);
+
+  fn into_any(self: Arc<Self>) -> Arc<dyn Any + Send + Sync>;
+}
+
+impl<E: Send + 'static> ErasedEvents for Mutex<EventBuffer<E>> {
+  fn begin_frame(&self) {
+    self.lock().begin_frame();
+  }
+
+  fn clear(&self) {
+    self.lock().clear();
+  }
+
+  fn into_any(self: Arc<Self>) -> Arc<dyn Any + Send + Sync> {
+    self
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn events_last_for_the_next_frame() {
+    let mut buffer = EventBuffer::default();
+    buffer.send(1);
+    buffer.begin_frame();
+    buffer.send(2);
+    assert_eq!(buffer.read_from(0).0, vec![1, 2]);
+
+    buffer.begin_frame();
+    assert_eq!(buffer.read_from(0).0, vec![2]);
+
+    buffer.begin_frame();
+    assert!(buffer.read_from(0).0.is_empty());
+  }
+
+  #[test]
+  fn cursors_only_see_new_events() {
+    let mut buffer = EventBuffer::default();
+    buffer.send('a');
+    let (events, cursor) = buffer.read_from(0);
+    assert_eq!(events, vec!['a']);
+
+    buffer.send('b');
+    let (events, cursor) = buffer.read_from(cursor);
+    assert_eq!(events, vec!['b']);
+    assert!(buffer.read_from(cursor).0.is_empty());
+  }
+}
@@ -0,0 +1,660 @@
+use std::{
+  any::{Any, TypeId},
+  collections::BTreeMap,
+  marker::PhantomData,
+  sync::Arc,
+};
+
+use parking_lot::{ArcRwLockReadGuard, ArcRwLockWriteGuard, RawRwLock};
+
+use super::{
+  entity::Entity,
+  storage::{ErasedStorage, Mut, Storage},
+  world::World,
+  Component,
+};
+use crate::{core::FoxyResult, foxy_error};
+
+/// One component type in an [`Access`], with a way to find its storage
+/// without knowing the type.
+#[derive(Debug, Clone)]
+struct Claim {
+  id: TypeId,
+  name: &'static str,
+  storage: fn(&World) -> Arc<dyn ErasedStorage>,
+}
+
+impl Claim {
+  fn of<T: Component>() -> Self {
+    Self {
+      id: TypeId::of::<T>(),
+      name: std::any::type_name::<T>(),
+      storage: |world| world.storage::<T>(),
+    }
+  }
+}
+
+/// Which component types something reads and writes. Systems whose access
+/// doesn't conflict can run at the same time.
+#[derive(Debug, Default, Clone)]
+pub struct Access {
+  reads: Vec<Claim>,
+  writes: Vec<Claim>,
+}
+
+impl Access {
+  pub fn read<T: Component>(&mut self) -> &mut Self {
+    self.reads.push(Claim::of::<T>());
+    self
+  }
+
+  pub fn write<T: Component>(&mut self) -> &mut Self {
+    self.writes.push(Claim::of::<T>());
+    self
+  }
+
+  /// Whether both can run at once, i.e. neither writes anything the other
+  /// touches.
+  pub fn is_compatible(&self, other: &Access) -> bool {
+    let touches = |access: &Access, id: TypeId| access.reads.iter().chain(&access.writes).any(|claim| claim.id == id);
+    !self.writes.iter().any(|claim| touches(other, claim.id))
+      && !other.writes.iter().any(|claim| touches(self, claim.id))
+  }
+
+  /// The first type `needed` uses that this doesn't allow, if any. Writing
+  /// allows reading.
+  pub(crate) fn missing(&self, needed: &Access) -> Option<&'static str> {
+    let allows = |list: &[Claim], id: TypeId| list.iter().any(|claim| claim.id == id);
+    needed
+      .writes
+      .iter()
+      .find(|claim| !allows(&self.writes, claim.id))
+      .or_else(|| {
+        needed
+          .reads
+          .iter()
+          .find(|claim| !allows(&self.writes, claim.id) && !allows(&self.reads, claim.id))
+      })
+      .map(|claim| claim.name)
+  }
+
+  /// A type that is written and also read or written elsewhere in the same
+  /// query, which would deadlock on its own lock.
+  fn self_conflict(&self) -> Option<&'static str> {
+    self.writes.iter().enumerate().find_map(|(i, claim)| {
+      let twice = self.writes[i + 1..]
+        .iter()
+        .chain(&self.reads)
+        .any(|other| other.id == claim.id);
+      twice.then_some(claim.name)
+    })
+  }
+}
+
+/// The storages of a query, locked up front in [`TypeId`] order. Two queries
+/// naming the same components in a different order would otherwise lock
+/// them in a different order too, and could deadlock each other.
+pub struct Locks {
+  writes: BTreeMap<TypeId, Box<dyn Any>>,
+  // Only held so nobody can write in between; queries take their own read
+  // guards, which can't block while these are held
+  reads: Vec<Box<dyn Any>>,
+}
+
+impl Locks {
+  fn acquire(world: &World, access: &Access) -> Self {
+    let mut claims: BTreeMap<TypeId, (&Claim, bool)> = BTreeMap::new();
+    for claim in &access.reads {
+      claims.entry(claim.id).or_insert((claim, false));
+    }
+    for claim in &access.writes {
+      claims.insert(claim.id, (claim, true));
+    }
+
+    let mut locks = Self {
+      writes: BTreeMap::new(),
+      reads: Vec::new(),
+    };
+    for (id, (claim, write)) in claims {
+      let storage = (claim.storage)(world);
+      match write {
+        true => {
+          locks.writes.insert(id, storage.write_erased());
+        }
+        false => locks.reads.push(storage.read_erased()),
+      }
+    }
+
+    locks
+  }
+
+  fn take_write<T: Component>(&mut self) -> ArcRwLockWriteGuard<RawRwLock, Storage<T>> {
+    let guard = self
+      .writes
+      .remove(&TypeId::of::<T>())
+      .expect("write lock taken twice or never acquired");
+    *guard.downcast().expect("storage locked as the wrong type")
+  }
+}
+
+/// Change ticks a query compares against: components changed after
+/// `last_run` count as changed, and writes are stamped with `this_run`.
+#[derive(Debug, Copy, Clone)]
+pub struct ChangeTicks {
+  pub(crate) last_run: u64,
+  pub(crate) this_run: u64,
+}
+
+/// What a [`Query`] yields per entity: `Entity`, `&T`, `&mut T` (as a
+/// [`Mut`]), `Option` of any of those, or tuples of up to eight.
+pub trait WorldQuery {
+  /// The locked storages.
+  type State: 'static;
+  type Item<'a>;
+
+  fn access(access: &mut Access);
+
+  fn lock(world: &World, locks: &mut Locks) -> Self::State;
+
+  /// The entities that could match, when known without looking at all of
+  /// them.
+  fn candidates(state: &Self::State) -> Option<&[Entity]>;
+
+  fn matches(state: &Self::State, entity: Entity) -> bool;
+
+  fn fetch<'a>(state: &'a mut Self::State, entity: Entity, ticks: ChangeTicks) -> Option<Self::Item<'a>>;
+}
+
+impl WorldQuery for Entity {
+  type Item<'a> = Entity;
+  type State = ();
+
+  fn access(_: &mut Access) {}
+
+  fn lock(_: &World, _: &mut Locks) -> Self::State {}
+
+  fn candidates(_: &Self::State) -> Option<&[Entity]> {
+    None
+  }
+
+  fn matches(_: &Self::State, _: Entity) -> bool {
+    true
+  }
+
+  fn fetch<'a>(_: &'a mut Self::State, entity: Entity, _: ChangeTicks) -> Option<Self::Item<'a>> {
+    Some(entity)
+  }
+}
+
+impl<T: Component> WorldQuery for &T {
+  type Item<'a> = &'a T;
+  type State = ArcRwLockReadGuard<RawRwLock, Storage<T>>;
+
+  fn access(access: &mut Access) {
+    access.read::<T>();
+  }
+
+  fn lock(world: &World, _: &mut Locks) -> Self::State {
+    world.storage::<T>().read_arc_recursive()
+  }
+
+  fn candidates(state: &Self::State) -> Option<&[Entity]> {
+    Some(state.entities())
+  }
+
+  fn matches(state: &Self::State, entity: Entity) -> bool {
+    state.contains(entity)
+  }
+
+  fn fetch<'a>(state: &'a mut Self::State, entity: Entity, _: ChangeTicks) -> Option<Self::Item<'a>> {
+    state.get(entity)
+  }
+}
+
+impl<T: Component> WorldQuery for &mut T {
+  type Item<'a> = Mut<'a, T>;
+  type State = ArcRwLockWriteGuard<RawRwLock, Storage<T>>;
+
+  fn access(access: &mut Access) {
+    access.write::<T>();
+  }
+
+  fn lock(_: &World, locks: &mut Locks) -> Self::State {
+    locks.take_write::<T>()
+  }
+
+  fn candidates(state: &Self::State) -> Option<&[Entity]> {
+    Some(state.entities())
+  }
+
+  fn matches(state: &Self::State, entity: Entity) -> bool {
+    state.contains(entity)
+  }
+
+  fn fetch<'a>(state: &'a mut Self::State, entity: Entity, ticks: ChangeTicks) -> Option<Self::Item<'a>> {
+    let (value, component_ticks) = state.get_mut(entity)?;
+    Some(Mut::new(value, component_ticks, ticks.this_run))
+  }
+}
+
+impl<Q: WorldQuery> WorldQuery for Option<Q> {
+  type Item<'a> = Option<Q::Item<'a>>;
+  type State = Q::State;
+
+  fn access(access: &mut Access) {
+    Q::access(access);
+  }
+
+  fn lock(world: &World, locks: &mut Locks) -> Self::State {
+    Q::lock(world, locks)
+  }
+
+  fn candidates(_: &Self::State) -> Option<&[Entity]> {
+    None
+  }
+
+  fn matches(_: &Self::State, _: Entity) -> bool {
+    true
+  }
+
+  fn fetch<'a>(state: &'a mut Self::State, entity: Entity, ticks: ChangeTicks) -> Option<Self::Item<'a>> {
+    Some(Q::fetch(state, entity, ticks))
+  }
+}
+
+fn smallest<'a>(sets: impl IntoIterator<Item = Option<&'a [Entity]>>) -> Option<&'a [Entity]> {
+  sets.into_iter().flatten().min_by_key(|set| set.len())
+}
+
+macro_rules! impl_world_query {
+  ($($name:ident),+) => {
+    impl<$($name: WorldQuery),+> WorldQuery for ($($name,)+) {
+      type State = ($($name::State,)+);
+      type Item<'a> = ($($name::Item<'a>,)+);
+
+      fn access(access: &mut Access) {
+        $($name::access(access);)+
+      }
+
+      fn lock(world: &World, locks: &mut Locks) -> Self::State {
+        ($($name::lock(world, locks),)+)
+      }
+
+      #[allow(non_snake_case)]
+      fn candidates(state: &Self::State) -> Option<&[Entity]> {
+        let ($($name,)+) = state;
+        smallest([$($name::candidates($name)),+])
+      }
+
+      #[allow(non_snake_case)]
+      fn matches(state: &Self::State, entity: Entity) -> bool {
+        let ($($name,)+) = state;
+        $($name::matches($name, entity))&&+
+      }
+
+      #[allow(non_snake_case)]
+      fn fetch<'a>(state: &'a mut Self::State, entity: Entity, ticks: ChangeTicks) -> Option<Self::Item<'a>> {
+        let ($($name,)+) = state;
+        Some(($($name::fetch($name, entity, ticks)?,)+))
+      }
+    }
+  };
+}
+
+impl_world_query!(A);
+impl_world_query!(A, B);
+impl_world_query!(A, B, C);
+impl_world_query!(A, B, C, D);
+impl_world_query!(A, B, C, D, E);
+impl_world_query!(A, B, C, D, E, F);
+impl_world_query!(A, B, C, D, E, F, G);
+impl_world_query!(A, B, C, D, E, F, G, H);
+
+/// Narrows a [`Query`] down without fetching anything: [`With`],
+/// [`Without`], [`Added`], [`Changed`], or tuples of them, which must all
+/// match.
+pub trait QueryFilter {
+  type State: 'static;
+
+  fn access(access: &mut Access);
+
+  fn lock(world: &World, locks: &mut Locks) -> Self::State;
+
+  fn candidates(state: &Self::State) -> Option<&[Entity]>;
+
+  fn matches(state: &Self::State, entity: Entity, ticks: ChangeTicks) -> bool;
+}
+
+impl QueryFilter for () {
+  type State = ();
+
+  fn access(_: &mut Access) {}
+
+  fn lock(_: &World, _: &mut Locks) -> Self::State {}
+
+  fn candidates(_: &Self::State) -> Option<&[Entity]> {
+    None
+  }
+
+  fn matches(_: &Self::State, _: Entity, _: ChangeTicks) -> bool {
+    true
+  }
+}
+
+/// Entities that have a `T`, without borrowing it.
+pub struct With<T>(PhantomData<T>);
+
+/// Entities that don't have a `T`.
+pub struct Without<T>(PhantomData<T>);
+
+/// Entities whose `T` was added since the system last ran.
+pub struct Added<T>(PhantomData<T>);
+
+/// Entities whose `T` was added or written to since the system last ran.
+pub struct Changed<T>(PhantomData<T>);
+
+macro_rules! impl_component_filter {
+  ($filter:ident, $candidates:expr, |$state:ident, $entity:ident, $ticks:ident| $matches:expr) => {
+    impl<T: Component> QueryFilter for $filter<T> {
+      type State = ArcRwLockReadGuard<RawRwLock, Storage<T>>;
+
+      fn access(access: &mut Access) {
+        access.read::<T>();
+      }
+
+      fn lock(world: &World, _: &mut Locks) -> Self::State {
+        world.storage::<T>().read_arc_recursive()
+      }
+
+      fn candidates(state: &Self::State) -> Option<&[Entity]> {
+        $candidates(state)
+      }
+
+      #[allow(unused_variables)]
+      fn matches($state: &Self::State, $entity: Entity, $ticks: ChangeTicks) -> bool {
+        $matches
+      }
+    }
+  };
+}
+
+fn present<T>(storage: &Storage<T>) -> Option<&[Entity]> {
+  Some(storage.entities())
+}
+
+fn unknown<T>(_: &Storage<T>) -> Option<&[Entity]> {
+  None
+}
+
+impl_component_filter!(With, present, |state, entity, ticks| state.contains(entity));
+impl_component_filter!(Without, unknown, |state, entity, ticks| !state.contains(entity));
+impl_component_filter!(Added, present, |state, entity, ticks| state
+  .ticks(entity)
+  .is_some_and(|component| component.added > ticks.last_run));
+impl_component_filter!(Changed, present, |state, entity, ticks| state
+  .ticks(entity)
+  .is_some_and(|component| component.changed > ticks.last_run));
+
+macro_rules! impl_query_filter {
+  ($($name:ident),+) => {
+    impl<$($name: QueryFilter),+> QueryFilter for ($($name,)+) {
+      type State = ($($name::State,)+);
+
+      fn access(access: &mut Access) {
+        $($name::access(access);)+
+      }
+
+      fn lock(world: &World, locks: &mut Locks) -> Self::State {
+        ($($name::lock(world, locks),)+)
+      }
+
+      #[allow(non_snake_case)]
+      fn candidates(state: &Self::State) -> Option<&[Entity]> {
+        let ($($name,)+) = state;
+        smallest([$($name::candidates($name)),+])
+      }
+
+      #[allow(non_snake_case)]
+      fn matches(state: &Self::State, entity: Entity, ticks: ChangeTicks) -> bool {
+        let ($($name,)+) = state;
+        $($name::matches($name, entity, ticks))&&+
+      }
+    }
+  };
+}
+
+impl_query_filter!(A);
+impl_query_filter!(A, B);
+impl_query_filter!(A, B, C);
+impl_query_filter!(A, B, C, D);
+
+/// Entities with the components in `Q` that pass the filter `F`, e.g.
+/// `Query<(&Velocity, &mut Position), Without<Frozen>>`.
+///
+/// A query holds the locks of its components for as long as it lives. Other
+/// queries reading the same components can run at the same time, but
+/// inserting, removing or despawning would wait on them, so do those through
+/// [`World::defer`] while iterating.
+pub struct Query<'w, Q: WorldQuery, F: QueryFilter = ()> {
+  world: &'w World,
+  state: Q::State,
+  filter: F::State,
+  ticks: ChangeTicks,
+}
+
+impl<'w, Q: WorldQuery, F: QueryFilter> Query<'w, Q, F> {
+  pub(crate) fn new(world: &'w World, ticks: ChangeTicks) -> FoxyResult<Self> {
+    let mut access = Access::default();
+    Q::access(&mut access);
+    F::access(&mut access);
+    if let Some(name) = access.self_conflict() {
+      return Err(foxy_error!("query borrows `{name}` mutably more than once"));
+    }
+
+    let mut locks = Locks::acquire(world, &access);
+    Ok(Self {
+      world,
+      state: Q::lock(world, &mut locks),
+      filter: F::lock(world, &mut locks),
+      ticks,
+    })
+  }
+
+  pub fn get(&mut self, entity: Entity) -> Option<Q::Item<'_>> {
+    if !self.contains(entity) {
+      return None;
+    }
+
+    Q::fetch(&mut self.state, entity, self.ticks)
+  }
+
+  pub fn contains(&self, entity: Entity) -> bool {
+    self.world.is_alive(entity) && Q::matches(&self.state, entity) && F::matches(&self.filter, entity, self.ticks)
+  }
+
+  pub fn for_each(&mut self, mut f: impl FnMut(Q::Item<'_>)) {
+    for entity in self.candidates() {
+      if let Some(item) = self.fetch(entity) {
+        f(item);
+      }
+    }
+  }
+
+  /// Like [`for_each`](Self::for_each), stopping at the first error.
+  pub fn try_for_each(&mut self, mut f: impl FnMut(Q::Item<'_>) -> FoxyResult<()>) -> FoxyResult<()> {
+    for entity in self.candidates() {
+      if let Some(item) = self.fetch(entity) {
+        f(item)?;
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Every matching entity, e.g. to despawn them once the query is dropped.
+  pub fn entities(&self) -> Vec<Entity> {
+    self
+      .candidates()
+      .into_iter()
+      .filter(|entity| Q::matches(&self.state, *entity) && F::matches(&self.filter, *entity, self.ticks))
+      .collect()
+  }
+
+  pub fn count(&self) -> usize {
+    self.entities().len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.count() == 0
+  }
+
+  fn fetch(&mut self, entity: Entity) -> Option<Q::Item<'_>> {
+    if !F::matches(&self.filter, entity, self.ticks) {
+      return None;
+    }
+
+    Q::fetch(&mut self.state, entity, self.ticks)
+  }
+
+  /// Copied out so items can borrow the storages while iterating.
+  fn candidates(&self) -> Vec<Entity> {
+    match smallest([Q::candidates(&self.state), F::candidates(&self.filter)]) {
+      Some(candidates) => candidates.to_vec(),
+      None => self.world.entities(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{sync::mpsc, thread, time::Duration};
+
+  use super::*;
+
+  #[derive(Debug, Default, PartialEq)]
+  struct Position(i32);
+  #[derive(Debug, Default, PartialEq)]
+  struct Velocity(i32);
+  struct Frozen;
+
+  #[test]
+  fn queries_join_components_and_filters() {
+    let world = World::default();
+    let moving = world.spawn((Position(0), Velocity(2)));
+    let frozen = world.spawn((Position(0), Velocity(5), Frozen));
+    let still = world.spawn((Position(7),));
+
+    world
+      .query_filtered::<(&Velocity, &mut Position), Without<Frozen>>()
+      .unwrap()
+      .for_each(|(velocity, mut position)| position.0 += velocity.0);
+
+    assert_eq!(*world.get::<Position>(moving).unwrap(), Position(2));
+    assert_eq!(*world.get::<Position>(frozen).unwrap(), Position(0));
+    assert_eq!(world.query_filtered::<Entity, With<Frozen>>().unwrap().entities(), vec![frozen]);
+
+    let mut query = world.query::<(Entity, Option<&Velocity>)>().unwrap();
+    assert_eq!(query.count(), 3);
+    assert_eq!(query.get(still), Some((still, None)));
+  }
+
+  #[test]
+  fn added_and_changed_compare_against_the_last_run() {
+    let world = World::default();
+    world.spawn((Position(0),));
+    let changed = |last_run| {
+      let ticks = ChangeTicks {
+        last_run,
+        this_run: world.increment_change_tick(),
+      };
+      Query::<Entity, Changed<Position>>::new(&world, ticks).unwrap().count()
+    };
+    let added = |last_run| {
+      let ticks = ChangeTicks {
+        last_run,
+        this_run: world.increment_change_tick(),
+      };
+      Query::<Entity, Added<Position>>::new(&world, ticks).unwrap().count()
+    };
+
+    let before = world.change_tick();
+    assert_eq!((added(0), changed(0)), (1, 1));
+    assert_eq!((added(before), changed(before)), (0, 0));
+
+    // Reading through `Mut` alone doesn't count as a change
+    world.query::<&mut Position>().unwrap().for_each(|position| {
+      let _ = position.0;
+    });
+    assert_eq!(changed(before), 0);
+
+    world
+      .query::<&mut Position>()
+      .unwrap()
+      .for_each(|mut position| position.0 += 1);
+    assert_eq!((added(before), changed(before)), (0, 1));
+  }
+
+  #[test]
+  fn borrowing_a_component_twice_mutably_is_an_error() {
+    let world = World::default();
+    assert!(world.query::<(&mut Position, &Position)>().is_err());
+    assert!(world.query_filtered::<&mut Position, Changed<Position>>().is_err());
+    assert!(world.query_filtered::<&Position, Changed<Position>>().is_ok());
+  }
+
+  #[test]
+  fn access_compatibility() {
+    let mut reads = Access::default();
+    reads.read::<Position>();
+    let mut other_reads = Access::default();
+    other_reads.read::<Position>().write::<Velocity>();
+    let mut writes = Access::default();
+    writes.write::<Position>();
+
+    assert!(reads.is_compatible(&other_reads));
+    assert!(!reads.is_compatible(&writes));
+    assert!(!writes.is_compatible(&reads));
+    assert_eq!(writes.missing(&reads), None);
+    assert!(reads.missing(&writes).is_some());
+  }
+
+  #[test]
+  fn opposite_tuple_orders_do_not_deadlock() {
+    let world = Arc::new(World::default());
+    for _ in 0..16 {
+      world.spawn((Position(0), Velocity(0)));
+    }
+
+    let (done, finished) = mpsc::channel();
+    for flip in [false, true] {
+      let world = world.clone();
+      let done = done.clone();
+      thread::spawn(move || {
+        for _ in 0..2000 {
+          if flip {
+            world
+              .query::<(&mut Velocity, &mut Position)>()
+              .unwrap()
+              .for_each(|(mut velocity, _)| {
+                velocity.0 += 1;
+              });
+          } else {
+            world
+              .query::<(&mut Position, &mut Velocity)>()
+              .unwrap()
+              .for_each(|(mut position, _)| {
+                position.0 += 1;
+              });
+          }
+        }
+        done.send(()).unwrap();
+      });
+    }
+
+    for _ in 0..2 {
+      finished
+        .recv_timeout(Duration::from_secs(30))
+        .expect("queries deadlocked");
+    }
+  }
+}
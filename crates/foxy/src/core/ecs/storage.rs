@@ -0,0 +1,285 @@
+use std::{
+  any::Any,
+  ops::{Deref, DerefMut},
+  sync::Arc,
+};
+
+use parking_lot::{ArcRwLockReadGuard, ArcRwLockWriteGuard, RawRwLock, RwLock};
+
+use super::{entity::Entity, Component};
+
+/// When a component was added and last changed, in
+/// [`World::change_tick`](super::World::change_tick)s.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct Ticks {
+  pub(crate) added: u64,
+  pub(crate) changed: u64,
+}
+
+/// Every `T` in the world, packed densely for iteration. `sparse` maps an
+/// entity's index to its slot in the dense arrays.
+pub struct Storage<T> {
+  sparse: Vec<Option<u32>>,
+  entities: Vec<Entity>,
+  components: Vec<T>,
+  ticks: Vec<Ticks>,
+}
+
+impl<T> Default for Storage<T> {
+  fn default() -> Self {
+    Self {
+      sparse: Vec::new(),
+      entities: Vec::new(),
+      components: Vec::new(),
+      ticks: Vec::new(),
+    }
+  }
+}
+
+impl<T> Storage<T> {
+  pub(crate) fn insert(&mut self, entity: Entity, component: T, tick: u64) -> Option<T> {
+    let index = entity.index() as usize;
+    if self.sparse.len() <= index {
+      self.sparse.resize(index + 1, None);
+    }
+
+    match self.sparse[index] {
+      Some(dense) => {
+        let dense = dense as usize;
+        // Only one generation of a slot can be alive, so anything else here
+        // belonged to a despawned entity and is overwritten
+        if self.entities[dense] != entity {
+          self.entities[dense] = entity;
+          self.ticks[dense] = Ticks {
+            added: tick,
+            changed: tick,
+          };
+          self.components[dense] = component;
+          return None;
+        }
+
+        self.ticks[dense].changed = tick;
+        Some(std::mem::replace(&mut self.components[dense], component))
+      }
+      None => {
+        self.sparse[index] = Some(self.entities.len() as u32);
+        self.entities.push(entity);
+        self.components.push(component);
+        self.ticks.push(Ticks {
+          added: tick,
+          changed: tick,
+        });
+        None
+      }
+    }
+  }
+
+  pub(crate) fn remove(&mut self, entity: Entity) -> Option<T> {
+    let dense = self.dense_index(entity)?;
+    self.sparse[entity.index() as usize] = None;
+    self.entities.swap_remove(dense);
+    self.ticks.swap_remove(dense);
+    let component = self.components.swap_remove(dense);
+    if let Some(moved) = self.entities.get(dense) {
+      self.sparse[moved.index() as usize] = Some(dense as u32);
+    }
+
+    Some(component)
+  }
+
+  pub(crate) fn contains(&self, entity: Entity) -> bool {
+    self.dense_index(entity).is_some()
+  }
+
+  pub(crate) fn get(&self, entity: Entity) -> Option<&T> {
+    self.dense_index(entity).map(|dense| &self.components[dense])
+  }
+
+  pub(crate) fn get_mut(&mut self, entity: Entity) -> Option<(&mut T, &mut Ticks)> {
+    let dense = self.dense_index(entity)?;
+    Some((&mut self.components[dense], &mut self.ticks[dense]))
+  }
+
+  pub(crate) fn ticks(&self, entity: Entity) -> Option<Ticks> {
+    self.dense_index(entity).map(|dense| self.ticks[dense])
+  }
+
+  pub(crate) fn entities(&self) -> &[Entity] {
+    &self.entities
+  }
+
+  pub(crate) fn dense_index(&self, entity: Entity) -> Option<usize> {
+    let dense = (*self.sparse.get(entity.index() as usize)?)? as usize;
+    (self.entities[dense] == entity).then_some(dense)
+  }
+}
+
+/// A component storage with its type erased, for despawning.
+pub(crate) trait ErasedStorage: Send + Sync {
+  fn remove_entity(&self, entity: Entity);
+
+  /// An `ArcRwLockReadGuard` of the storage.
+  fn read_erased(self: Arc<Self>) -> Box<dyn Any>;
+
+  /// An `ArcRwLockWriteGuard` of the storage.
+  fn write_erased(self: Arc<Self>) -> Box<dyn Any>;
+
+  fn into_any(self: Arc<Self>) -> Arc<dyn Any + Send + Sync>;
+}
+
+impl<T: Component> ErasedStorage for RwLock<Storage<T>> {
+  fn remove_entity(&self, entity: Entity) {
+    self.write().remove(entity);
+  }
+
+  fn read_erased(self: Arc<Self>) -> Box<dyn Any> {
+    Box::new(self.read_arc_recursive())
+  }
+
+  fn write_erased(self: Arc<Self>) -> Box<dyn Any> {
+    Box::new(self.write_arc())
+  }
+
+  fn into_any(self: Arc<Self>) -> Arc<dyn Any + Send + Sync> {
+    self
+  }
+}
+
+/// Shared access to one entity's `T`, from [`World::get`](super::World::get).
+/// Keeps every `T` readable but not writable until dropped.
+pub struct Ref<T: Component> {
+  storage: ArcRwLockReadGuard<RawRwLock, Storage<T>>,
+  dense: usize,
+}
+
+impl<T: Component> Ref<T> {
+  pub(crate) fn new(storage: ArcRwLockReadGuard<RawRwLock, Storage<T>>, entity: Entity) -> Option<Self> {
+    let dense = storage.dense_index(entity)?;
+    Some(Self { storage, dense })
+  }
+}
+
+impl<T: Component> Deref for Ref<T> {
+  type Target = T;
+
+  fn deref(&self) -> &T {
+    &self.storage.components[self.dense]
+  }
+}
+
+/// Exclusive access to one entity's `T`, from
+/// [`World::get_mut`](super::World::get_mut). Marks the component changed
+/// once it is written through. Locks every `T` until dropped.
+pub struct RefMut<T: Component> {
+  storage: ArcRwLockWriteGuard<RawRwLock, Storage<T>>,
+  dense: usize,
+  tick: u64,
+}
+
+impl<T: Component> RefMut<T> {
+  pub(crate) fn new(storage: ArcRwLockWriteGuard<RawRwLock, Storage<T>>, entity: Entity, tick: u64) -> Option<Self> {
+    let dense = storage.dense_index(entity)?;
+    Some(Self { storage, dense, tick })
+  }
+}
+
+impl<T: Component> Deref for RefMut<T> {
+  type Target = T;
+
+  fn deref(&self) -> &T {
+    &self.storage.components[self.dense]
+  }
+}
+
+impl<T: Component> DerefMut for RefMut<T> {
+  fn deref_mut(&mut self) -> &mut T {
+    let storage = &mut *self.storage;
+    storage.ticks[self.dense].changed = self.tick;
+    &mut storage.components[self.dense]
+  }
+}
+
+/// A component borrowed from a [`Query`](super::Query) for writing. Marks it
+/// changed once it is written through, so reading alone doesn't trip
+/// [`Changed`](super::Changed) filters.
+pub struct Mut<'a, T> {
+  value: &'a mut T,
+  ticks: &'a mut Ticks,
+  tick: u64,
+}
+
+impl<'a, T> Mut<'a, T> {
+  pub(crate) fn new(value: &'a mut T, ticks: &'a mut Ticks, tick: u64) -> Self {
+    Self { value, ticks, tick }
+  }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for Mut<'_, T> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    self.value.fmt(f)
+  }
+}
+
+impl<T> Deref for Mut<'_, T> {
+  type Target = T;
+
+  fn deref(&self) -> &T {
+    self.value
+  }
+}
+
+impl<T> DerefMut for Mut<'_, T> {
+  fn deref_mut(&mut self) -> &mut T {
+    self.ticks.changed = self.tick;
+    self.value
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::core::ecs::entity::Entities;
+
+  #[test]
+  fn removing_keeps_the_moved_entity_reachable() {
+    let mut entities = Entities::default();
+    let (a, b, c) = (entities.alloc(), entities.alloc(), entities.alloc());
+    let mut storage = Storage::default();
+    storage.insert(a, 'a', 1);
+    storage.insert(b, 'b', 1);
+    storage.insert(c, 'c', 1);
+
+    assert_eq!(storage.remove(a), Some('a'));
+    assert_eq!(storage.remove(a), None);
+    assert_eq!(storage.get(b), Some(&'b'));
+    assert_eq!(storage.get(c), Some(&'c'));
+    assert_eq!(storage.entities().len(), 2);
+  }
+
+  #[test]
+  fn replacing_marks_changed_but_not_added() {
+    let mut entities = Entities::default();
+    let entity = entities.alloc();
+    let mut storage = Storage::default();
+    assert_eq!(storage.insert(entity, 1, 3), None);
+    assert_eq!(storage.insert(entity, 2, 5), Some(1));
+    assert_eq!(storage.ticks(entity), Some(Ticks { added: 3, changed: 5 }));
+  }
+
+  #[test]
+  fn a_stale_generation_is_overwritten() {
+    let mut entities = Entities::default();
+    let old = entities.alloc();
+    let mut storage = Storage::default();
+    storage.insert(old, "old", 1);
+
+    // Left behind as if a despawn raced with the insert
+    entities.free(old);
+    let new = entities.alloc();
+    assert_eq!(storage.insert(new, "new", 2), None);
+    assert_eq!(storage.get(old), None);
+    assert_eq!(storage.get(new), Some(&"new"));
+    assert_eq!(storage.entities(), &[new]);
+    assert_eq!(storage.ticks(new), Some(Ticks { added: 2, changed: 2 }));
+  }
+}
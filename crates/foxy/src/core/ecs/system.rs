@@ -0,0 +1,219 @@
+use std::{any::TypeId, borrow::Cow, collections::HashMap};
+
+use super::{
+  query::{Access, ChangeTicks, Query, QueryFilter, WorldQuery},
+  world::World,
+  Component,
+};
+use crate::{
+  core::{event::FoxyEvent, foxy_state::Foxy, FoxyResult},
+  foxy_error,
+};
+
+type Run = Box<dyn FnMut(&mut SystemContext<'_>) -> FoxyResult<()> + Send>;
+
+/// A system that works on the [`World`], added with
+/// [`Schedule::add_world_system`](crate::core::schedule::Schedule::add_world_system).
+///
+/// Systems declare the components they read and write up front. Their
+/// queries are checked against that, which lets the parallel executor run
+/// systems that don't conflict at the same time, and lets
+/// [`Added`](super::Added) and [`Changed`](super::Changed) filters see what
+/// changed since the system last ran.
+pub struct WorldSystem {
+  name: Cow<'static, str>,
+  access: Access,
+  run: Run,
+  last_run: u64,
+  event_cursors: HashMap<TypeId, u64>,
+}
+
+impl std::fmt::Debug for WorldSystem {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("WorldSystem")
+      .field("name", &self.name)
+      .field("access", &self.access)
+      .finish_non_exhaustive()
+  }
+}
+
+impl WorldSystem {
+  pub fn new(
+    name: impl Into<Cow<'static, str>>,
+    run: impl FnMut(&mut SystemContext<'_>) -> FoxyResult<()> + Send + 'static,
+  ) -> Self {
+    Self {
+      name: name.into(),
+      access: Access::default(),
+      run: Box::new(run),
+      last_run: 0,
+      event_cursors: HashMap::new(),
+    }
+  }
+
+  pub fn reads<T: Component>(mut self) -> Self {
+    self.access.read::<T>();
+    self
+  }
+
+  pub fn writes<T: Component>(mut self) -> Self {
+    self.access.write::<T>();
+    self
+  }
+
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  pub fn access(&self) -> &Access {
+    &self.access
+  }
+
+  pub(crate) fn run(&mut self, foxy: &Foxy, events: &[FoxyEvent]) -> FoxyResult<()> {
+    let world = foxy.world();
+    let this_run = world.increment_change_tick();
+    let mut context = SystemContext {
+      foxy,
+      world,
+      events,
+      name: &self.name,
+      access: &self.access,
+      ticks: ChangeTicks {
+        last_run: self.last_run,
+        this_run,
+      },
+      event_cursors: &mut self.event_cursors,
+    };
+
+    let result = (self.run)(&mut context);
+    self.last_run = this_run;
+    result
+  }
+}
+
+/// What a [`WorldSystem`] gets each time it runs.
+///
+/// `world` is there for spawning, despawning and events. Querying through it
+/// directly skips the access check, and can deadlock against systems running
+/// in parallel, so use [`query`](Self::query) instead.
+pub struct SystemContext<'a> {
+  pub foxy: &'a Foxy,
+  pub world: &'a World,
+  /// The events of the phase the system runs in.
+  pub events: &'a [FoxyEvent],
+  name: &'a str,
+  access: &'a Access,
+  ticks: ChangeTicks,
+  event_cursors: &'a mut HashMap<TypeId, u64>,
+}
+
+impl<'a> SystemContext<'a> {
+  /// Fails if `Q` touches components the system didn't declare.
+  pub fn query<Q: WorldQuery>(&self) -> FoxyResult<Query<'a, Q>> {
+    self.query_filtered::<Q, ()>()
+  }
+
+  pub fn query_filtered<Q: WorldQuery, F: QueryFilter>(&self) -> FoxyResult<Query<'a, Q, F>> {
+    let mut needed = Access::default();
+    Q::access(&mut needed);
+    F::access(&mut needed);
+    if let Some(component) = self.access.missing(&needed) {
+      return Err(foxy_error!("system `{}` queries `{component}` without declaring it", self.name));
+    }
+
+    Query::new(self.world, self.ticks)
+  }
+
+  /// The `E`s sent since this system last read them.
+  pub fn read_events<E: Clone + Send + 'static>(&mut self) -> Vec<E> {
+    let cursor = self.event_cursors.entry(TypeId::of::<E>()).or_default();
+    let (events, next) = self.world.read_events_from::<E>(*cursor);
+    *cursor = next;
+    events
+  }
+
+  pub fn send_event<E: Clone + Send + 'static>(&self, event: E) {
+    self.world.send_event(event);
+  }
+
+  /// The change tick of the system's previous run, or 0 on its first.
+  pub fn last_run(&self) -> u64 {
+    self.ticks.last_run
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+  };
+
+  use foxy_utils::time::EngineTime;
+
+  use super::*;
+  use crate::core::{
+    ecs::{Changed, Entity},
+    foxy_state::State,
+  };
+
+  #[derive(Clone)]
+  struct Score(u32);
+  #[derive(Clone, Debug, PartialEq)]
+  struct Scored(u32);
+
+  fn foxy() -> Foxy {
+    Foxy::new(State::headless(EngineTime::default()))
+  }
+
+  #[test]
+  fn undeclared_access_is_an_error() {
+    let foxy = foxy();
+    let mut system = WorldSystem::new("sneaky", |ctx| ctx.query::<&mut Score>().map(|_| ()));
+    assert!(system.run(&foxy, &[]).is_err());
+
+    let mut system = WorldSystem::new("honest", |ctx| ctx.query::<&mut Score>().map(|_| ())).writes::<Score>();
+    assert!(system.run(&foxy, &[]).is_ok());
+  }
+
+  #[test]
+  fn changes_are_seen_once_per_system() {
+    let foxy = foxy();
+    let entity = foxy.world().spawn((Score(0),));
+    let seen = Arc::new(AtomicUsize::new(0));
+    let counter = seen.clone();
+    let mut watcher = WorldSystem::new("watcher", move |ctx| {
+      let changed = ctx.query_filtered::<Entity, Changed<Score>>()?.count();
+      counter.store(changed, Ordering::SeqCst);
+      Ok(())
+    })
+    .reads::<Score>();
+
+    watcher.run(&foxy, &[]).unwrap();
+    assert_eq!(seen.load(Ordering::SeqCst), 1);
+    watcher.run(&foxy, &[]).unwrap();
+    assert_eq!(seen.load(Ordering::SeqCst), 0);
+
+    foxy.world().get_mut::<Score>(entity).unwrap().0 += 1;
+    watcher.run(&foxy, &[]).unwrap();
+    assert_eq!(seen.load(Ordering::SeqCst), 1);
+  }
+
+  #[test]
+  fn each_system_reads_an_event_once() {
+    let foxy = foxy();
+    let received = Arc::new(parking_lot::Mutex::new(Vec::new()));
+    let log = received.clone();
+    let mut reader = WorldSystem::new("reader", move |ctx| {
+      log.lock().extend(ctx.read_events::<Scored>());
+      Ok(())
+    });
+
+    foxy.world().send_event(Scored(1));
+    reader.run(&foxy, &[]).unwrap();
+    reader.run(&foxy, &[]).unwrap();
+    foxy.world().send_event(Scored(2));
+    reader.run(&foxy, &[]).unwrap();
+    assert_eq!(*received.lock(), vec![Scored(1), Scored(2)]);
+  }
+}
@@ -0,0 +1,323 @@
+use std::{
+  any::TypeId,
+  collections::HashMap,
+  sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+  },
+};
+
+use parking_lot::{Mutex, RwLock};
+
+use super::{
+  bundle::Bundle,
+  entity::{Entities, Entity},
+  event::{ErasedEvents, EventBuffer},
+  query::{ChangeTicks, Query, QueryFilter, WorldQuery},
+  storage::{ErasedStorage, Ref, RefMut, Storage},
+  Component,
+};
+use crate::{core::FoxyResult, foxy_error};
+
+type Command = Box<dyn FnOnce(&World) + Send>;
+
+/// Entities, their components, and events between systems. Reached through
+/// [`Foxy::world`](crate::core::foxy_state::Foxy::world).
+///
+/// Everything takes `&self`: each component type and event type has its own
+/// lock, so the world can be used from any thread and from systems running
+/// in parallel. Holding a [`Query`], [`Ref`] or [`RefMut`] keeps that
+/// component's lock, and structural changes to it wait until they are
+/// dropped. Use [`defer`](Self::defer) for changes made while iterating.
+pub struct World {
+  entities: Mutex<Entities>,
+  storages: RwLock<HashMap<TypeId, Arc<dyn ErasedStorage>>>,
+  events: RwLock<HashMap<TypeId, Arc<dyn ErasedEvents>>>,
+  change_tick: AtomicU64,
+  frame_tick: AtomicU64,
+  deferred: Mutex<Vec<Command>>,
+}
+
+impl Default for World {
+  fn default() -> Self {
+    Self {
+      entities: Mutex::default(),
+      storages: RwLock::default(),
+      events: RwLock::default(),
+      // Systems start with a last run of 0, so they see everything that
+      // existed before their first run as added
+      change_tick: AtomicU64::new(1),
+      frame_tick: AtomicU64::new(0),
+      deferred: Mutex::default(),
+    }
+  }
+}
+
+impl std::fmt::Debug for World {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("World")
+      .field("entities", &self.len())
+      .field("components", &self.storages.read().len())
+      .finish_non_exhaustive()
+  }
+}
+
+impl World {
+  pub fn spawn(&self, bundle: impl Bundle) -> Entity {
+    let entity = self.entities.lock().alloc();
+    bundle.insert_into(self, entity);
+    entity
+  }
+
+  /// Removes `entity` and all its components. Returns whether it was alive.
+  pub fn despawn(&self, entity: Entity) -> bool {
+    // Freed first so nothing new can be attached while the components go
+    if !self.entities.lock().free(entity) {
+      return false;
+    }
+
+    let storages: Vec<_> = self.storages.read().values().cloned().collect();
+    for storage in storages {
+      storage.remove_entity(entity);
+    }
+
+    true
+  }
+
+  pub fn is_alive(&self, entity: Entity) -> bool {
+    self.entities.lock().is_alive(entity)
+  }
+
+  /// How many entities are alive.
+  pub fn len(&self) -> usize {
+    self.entities.lock().len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// Every entity that is alive.
+  pub fn entities(&self) -> Vec<Entity> {
+    self.entities.lock().iter().collect()
+  }
+
+  /// Attaches `component` to `entity`, or replaces and returns the `T` it
+  /// already had. Fails if `entity` was despawned.
+  pub fn insert<T: Component>(&self, entity: Entity, component: T) -> FoxyResult<Option<T>> {
+    let storage = self.storage::<T>();
+    // Checked under the storage lock so a concurrent despawn either happens
+    // first or cleans up after this
+    let mut storage = storage.write();
+    if !self.is_alive(entity) {
+      return Err(foxy_error!("{entity:?} was despawned"));
+    }
+
+    Ok(storage.insert(entity, component, self.increment_change_tick()))
+  }
+
+  pub fn remove<T: Component>(&self, entity: Entity) -> Option<T> {
+    self.storage::<T>().write().remove(entity)
+  }
+
+  pub fn has<T: Component>(&self, entity: Entity) -> bool {
+    self.storage::<T>().read_recursive().contains(entity)
+  }
+
+  pub fn get<T: Component>(&self, entity: Entity) -> Option<Ref<T>> {
+    Ref::new(self.storage::<T>().read_arc_recursive(), entity)
+  }
+
+  pub fn get_mut<T: Component>(&self, entity: Entity) -> Option<RefMut<T>> {
+    RefMut::new(self.storage::<T>().write_arc(), entity, self.increment_change_tick())
+  }
+
+  /// Entities with everything in `Q`, e.g.
+  /// `world.query::<(Entity, &mut Position)>()`. [`Added`](super::Added) and
+  /// [`Changed`](super::Changed) filters see changes made since the current
+  /// frame began; systems get changes since they last ran instead.
+  pub fn query<Q: WorldQuery>(&self) -> FoxyResult<Query<'_, Q>> {
+    self.query_filtered::<Q, ()>()
+  }
+
+  pub fn query_filtered<Q: WorldQuery, F: QueryFilter>(&self) -> FoxyResult<Query<'_, Q, F>> {
+    let ticks = ChangeTicks {
+      last_run: self.frame_tick.load(Ordering::Acquire),
+      this_run: self.increment_change_tick(),
+    };
+    Query::new(self, ticks)
+  }
+
+  /// Sends an event to every system reading `E`. Events are kept until the
+  /// end of the next frame.
+  pub fn send_event<E: Clone + Send + 'static>(&self, event: E) {
+    self.event_buffer::<E>().lock().send(event);
+  }
+
+  /// Every `E` sent this frame or the last. Systems should use
+  /// [`SystemContext::read_events`](super::SystemContext::read_events), which
+  /// only returns each event once.
+  pub fn events<E: Clone + Send + 'static>(&self) -> Vec<E> {
+    self.read_events_from::<E>(0).0
+  }
+
+  /// Despawns every entity and drops pending events and deferred commands.
+  /// Entities spawned afterwards never reuse an old handle.
+  pub fn clear(&self) {
+    self.deferred.lock().clear();
+    for entity in self.entities() {
+      self.despawn(entity);
+    }
+
+    let events: Vec<_> = self.events.read().values().cloned().collect();
+    for buffer in events {
+      buffer.clear();
+    }
+  }
+
+  /// Queues a change to apply once the current system finishes, e.g. to
+  /// despawn entities found by a query that is still borrowing them.
+  pub fn defer(&self, command: impl FnOnce(&World) + Send + 'static) {
+    self.deferred.lock().push(Box::new(command));
+  }
+
+  /// A counter that moves forward with every change, for comparing when
+  /// components last changed.
+  pub fn change_tick(&self) -> u64 {
+    self.change_tick.load(Ordering::Acquire)
+  }
+
+  pub(crate) fn increment_change_tick(&self) -> u64 {
+    self.change_tick.fetch_add(1, Ordering::AcqRel) + 1
+  }
+
+  /// Ages events and moves the frame tick that direct queries compare
+  /// against. Called once at the start of every frame.
+  pub(crate) fn begin_frame(&self) {
+    self.frame_tick.store(self.increment_change_tick(), Ordering::Release);
+
+    let events: Vec<_> = self.events.read().values().cloned().collect();
+    for buffer in events {
+      buffer.begin_frame();
+    }
+  }
+
+  /// Runs deferred commands, including any they defer in turn.
+  pub(crate) fn apply_deferred(&self) {
+    loop {
+      let commands = std::mem::take(&mut *self.deferred.lock());
+      if commands.is_empty() {
+        break;
+      }
+
+      for command in commands {
+        command(self);
+      }
+    }
+  }
+
+  pub(crate) fn read_events_from<E: Clone + Send + 'static>(&self, cursor: u64) -> (Vec<E>, u64) {
+    self.event_buffer::<E>().lock().read_from(cursor)
+  }
+
+  pub(crate) fn storage<T: Component>(&self) -> Arc<RwLock<Storage<T>>> {
+    let id = TypeId::of::<T>();
+    let existing = self.storages.read().get(&id).cloned();
+    let storage = existing.unwrap_or_else(|| {
+      self
+        .storages
+        .write()
+        .entry(id)
+        .or_insert_with(|| Arc::new(RwLock::new(Storage::<T>::default())))
+        .clone()
+    });
+
+    storage
+      .into_any()
+      .downcast()
+      .expect("component storage keyed by the wrong type")
+  }
+
+  fn event_buffer<E: Clone + Send + 'static>(&self) -> Arc<Mutex<EventBuffer<E>>> {
+    let id = TypeId::of::<E>();
+    let existing = self.events.read().get(&id).cloned();
+    let buffer = existing.unwrap_or_else(|| {
+      self
+        .events
+        .write()
+        .entry(id)
+        .or_insert_with(|| Arc::new(Mutex::new(EventBuffer::<E>::default())))
+        .clone()
+    });
+
+    buffer
+      .into_any()
+      .downcast()
+      .expect("event buffer keyed by the wrong type")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[derive(Debug, PartialEq)]
+  struct Health(u32);
+
+  #[test]
+  fn despawning_removes_components() {
+    let world = World::default();
+    let entity = world.spawn((Health(3), 'x'));
+    assert!(world.has::<Health>(entity));
+
+    assert!(world.despawn(entity));
+    assert!(!world.despawn(entity));
+    assert!(!world.has::<Health>(entity));
+    assert!(world.get::<char>(entity).is_none());
+    assert!(world.insert(entity, Health(1)).is_err());
+    assert!(world.is_empty());
+  }
+
+  #[test]
+  fn insert_replaces_and_get_mut_writes() {
+    let world = World::default();
+    let entity = world.spawn(());
+    assert_eq!(world.insert(entity, Health(1)).unwrap(), None);
+    assert_eq!(world.insert(entity, Health(2)).unwrap(), Some(Health(1)));
+
+    world.get_mut::<Health>(entity).unwrap().0 += 1;
+    assert_eq!(*world.get::<Health>(entity).unwrap(), Health(3));
+    assert_eq!(world.remove::<Health>(entity), Some(Health(3)));
+  }
+
+  #[test]
+  fn deferred_commands_run_once_applied() {
+    let world = World::default();
+    let entity = world.spawn((Health(1),));
+    world.defer(move |world| {
+      world.despawn(entity);
+      world.defer(|world| {
+        world.spawn(());
+      });
+    });
+    assert!(world.is_alive(entity));
+
+    world.apply_deferred();
+    assert!(!world.is_alive(entity));
+    assert_eq!(world.len(), 1);
+  }
+
+  #[test]
+  fn clear_empties_the_world_without_reusing_handles() {
+    let world = World::default();
+    let entity = world.spawn((Health(1),));
+    world.send_event(5u8);
+    world.clear();
+
+    assert!(world.is_empty());
+    assert!(world.events::<u8>().is_empty());
+    let next = world.spawn(());
+    assert_ne!(next, entity);
+    assert!(!world.is_alive(entity));
+  }
+}
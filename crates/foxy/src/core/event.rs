@@ -1,9 +1,21 @@
-use winit::event::KeyEvent;
+use winit::{
+  dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize},
+  event::{ElementState, KeyEvent},
+};
 
-use super::input::{
-  key::KeyCode,
-  mouse::MouseCode,
-  state::{ButtonState, KeyState},
+use super::{
+  drag_drop::DragDropEvent,
+  input::{
+    gamepad::{GamepadAxis, GamepadButton, GamepadId},
+    gesture::Gesture,
+    key::KeyCode,
+    logical::LogicalKey,
+    mouse::{MouseCode, ScrollDelta},
+    state::{ButtonState, KeyState},
+    touch::Touch,
+    Input,
+  },
+  window_command::{MonitorId, MonitorInfo, WindowCommand},
 };
 
 #[derive(Debug, Clone, PartialEq)]
@@ -11,48 +23,209 @@ pub enum FoxyEvent {
   None,
   Window(WindowEvent),
   Input(InputEvent),
+  DragDrop(DragDropEvent),
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum WindowEvent {
-  Moved,
-  Resized,
-  Rescaled,
+  /// New position of the window's top-left corner, in physical pixels.
+  Moved(PhysicalPosition<i32>),
+  /// New size of the window's client area.
+  Resized {
+    physical: PhysicalSize<u32>,
+    logical: LogicalSize<f64>,
+  },
+  /// The window moved to a monitor with a different scale factor, or the
+  /// user changed it.
+  Rescaled {
+    scale_factor: f64,
+  },
+  FocusGained,
+  FocusLost,
+  /// The window became fully hidden (`true`) or visible again (`false`),
+  /// e.g. minimized or covered. Not reported on every platform.
+  Occluded(bool),
+  ThemeChanged(Theme),
+  CursorEntered,
+  CursorLeft,
+  /// The user asked to close the window and
+  /// [`Runnable::stop`](super::runnable::Runnable::stop) returned anything but
+  /// [`Flow::Exit`](super::runnable::Flow::Exit). After
+  /// [`Flow::Restart`](super::runnable::Flow::Restart) it reaches the new app.
+  CloseRequested,
+  /// Answer to [`WindowCommand::QueryMonitors`].
+  Monitors {
+    monitors: Vec<MonitorInfo>,
+    current: Option<MonitorId>,
+  },
+  CommandFailed {
+    command: WindowCommand,
+    reason: String,
+  },
   Unmapped(winit::event::WindowEvent),
 }
 
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Theme {
+  Light,
+  Dark,
+}
+
+impl From<winit::window::Theme> for Theme {
+  fn from(value: winit::window::Theme) -> Self {
+    match value {
+      winit::window::Theme::Light => Theme::Light,
+      winit::window::Theme::Dark => Theme::Dark,
+    }
+  }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum InputEvent {
   Mouse(MouseCode, ButtonState),
-  Keyboard(KeyCode, KeyState),
-  Cursor,
-  Scroll,
+  /// The physical key, the key the current layout maps it to, and its state.
+  Keyboard(KeyCode, LogicalKey, KeyState),
+  Cursor {
+    physical: PhysicalPosition<f64>,
+    logical: LogicalPosition<f64>,
+  },
+  /// Raw, unaccelerated mouse movement from the device. Unlike `Cursor`, this
+  /// keeps reporting at the edges of the screen and while the cursor is
+  /// grabbed.
+  MouseMotion {
+    delta: (f64, f64),
+  },
+  Scroll(ScrollDelta),
+  /// Characters typed by the user, after keyboard layout and dead keys are
+  /// applied. Control characters (backspace, enter, ...) are left to
+  /// `Keyboard` events.
+  Text(String),
+  Ime(ImeEvent),
+  Touch(Touch),
+  /// Touchpad pinch. Positive values zoom in. Also sent as a
+  /// [`Gesture::Pinch`].
+  TouchpadMagnify {
+    delta: f64,
+  },
+  /// Touchpad rotation in degrees, counterclockwise positive. Also sent as a
+  /// [`Gesture::Rotate`].
+  TouchpadRotate {
+    degrees: f32,
+  },
+  Gesture(Gesture),
+  GamepadConnected {
+    id: GamepadId,
+    name: String,
+  },
+  /// Sent once per gamepad; any buttons it still held are released first.
+  GamepadDisconnected {
+    id: GamepadId,
+  },
+  GamepadButton {
+    id: GamepadId,
+    button: GamepadButton,
+    state: ButtonState,
+  },
+  /// Raw axis value, before dead zones. Use
+  /// [`Input::gamepad_axis`](super::input::Input::gamepad_axis) for the
+  /// filtered value.
+  GamepadAxis {
+    id: GamepadId,
+    axis: GamepadAxis,
+    value: f32,
+  },
+}
+
+/// Input method editor events, sent once IME is enabled with
+/// [`State::set_ime_allowed`](super::foxy_state::State::set_ime_allowed).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImeEvent {
+  Enabled,
+  /// Text being composed. `cursor` is a byte range into `text`, and is `None`
+  /// when the cursor should be hidden. An empty `text` clears the preedit.
+  Preedit {
+    text: String,
+    cursor: Option<(usize, usize)>,
+  },
+  /// Composition finished; insert `text`.
+  Commit(String),
+  Disabled,
+}
+
+impl From<winit::event::Ime> for ImeEvent {
+  fn from(value: winit::event::Ime) -> Self {
+    match value {
+      winit::event::Ime::Enabled => ImeEvent::Enabled,
+      winit::event::Ime::Preedit(text, cursor) => ImeEvent::Preedit { text, cursor },
+      winit::event::Ime::Commit(text) => ImeEvent::Commit(text),
+      winit::event::Ime::Disabled => ImeEvent::Disabled,
+    }
+  }
 }
 
-impl From<Option<winit::event::WindowEvent>> for FoxyEvent {
-  fn from(value: Option<winit::event::WindowEvent>) -> Self {
-    let Some(value) = value else {
-      return Self::None;
-    };
+impl FoxyEvent {
+  /// Printable text produced by a key press, if any.
+  pub(crate) fn text_from_winit(event: &KeyEvent) -> Option<Self> {
+    if event.state != ElementState::Pressed {
+      return None;
+    }
 
+    let text = event.text.as_ref()?;
+    if text.chars().all(char::is_control) {
+      return None;
+    }
+
+    Some(Self::Input(InputEvent::Text(text.to_string())))
+  }
+
+  /// Converts a winit event. `input` provides the current scale factor, used
+  /// to derive logical positions, and the cursor position for drag-and-drop.
+  pub fn from_winit(value: winit::event::WindowEvent, input: &Input) -> Self {
+    let scale_factor = input.scale_factor();
     match value {
-      winit::event::WindowEvent::Resized(_) => Self::Window(WindowEvent::Resized),
-      winit::event::WindowEvent::Moved(_) => Self::Window(WindowEvent::Moved),
-      winit::event::WindowEvent::KeyboardInput {
-        event: KeyEvent {
-          physical_key,
-          state,
-          repeat,
-          ..
-        },
-        ..
-      } => FoxyEvent::Input(InputEvent::Keyboard(physical_key.into(), KeyState::from_winit(state, repeat))),
-      winit::event::WindowEvent::CursorMoved { .. } => Self::Input(InputEvent::Cursor),
-      winit::event::WindowEvent::MouseWheel { .. } => Self::Input(InputEvent::Scroll),
+      winit::event::WindowEvent::Resized(size) => Self::Window(WindowEvent::Resized {
+        physical: size,
+        logical: size.to_logical(scale_factor),
+      }),
+      winit::event::WindowEvent::Moved(position) => Self::Window(WindowEvent::Moved(position)),
+      winit::event::WindowEvent::Focused(true) => Self::Window(WindowEvent::FocusGained),
+      winit::event::WindowEvent::Focused(false) => Self::Window(WindowEvent::FocusLost),
+      winit::event::WindowEvent::Occluded(occluded) => Self::Window(WindowEvent::Occluded(occluded)),
+      winit::event::WindowEvent::ThemeChanged(theme) => Self::Window(WindowEvent::ThemeChanged(theme.into())),
+      winit::event::WindowEvent::CursorEntered { .. } => Self::Window(WindowEvent::CursorEntered),
+      winit::event::WindowEvent::CursorLeft { .. } => Self::Window(WindowEvent::CursorLeft),
+      winit::event::WindowEvent::HoveredFile(path) => Self::DragDrop(DragDropEvent::HoverEnter {
+        path,
+        position: input.cursor_position(),
+      }),
+      winit::event::WindowEvent::HoveredFileCancelled => Self::DragDrop(DragDropEvent::HoverLeave),
+      winit::event::WindowEvent::DroppedFile(path) => Self::DragDrop(DragDropEvent::Drop {
+        path,
+        position: input.cursor_position(),
+      }),
+      winit::event::WindowEvent::CloseRequested => Self::Window(WindowEvent::CloseRequested),
+      winit::event::WindowEvent::KeyboardInput { event, .. } => FoxyEvent::Input(InputEvent::Keyboard(
+        event.physical_key.into(),
+        LogicalKey::from_key_event(&event),
+        KeyState::from_winit(event.state, event.repeat),
+      )),
+      winit::event::WindowEvent::CursorMoved { position, .. } => Self::Input(InputEvent::Cursor {
+        physical: position,
+        logical: position.to_logical(scale_factor),
+      }),
+      winit::event::WindowEvent::MouseWheel { delta, .. } => Self::Input(InputEvent::Scroll(delta.into())),
       winit::event::WindowEvent::MouseInput { state, button, .. } => {
         FoxyEvent::Input(InputEvent::Mouse(button.into(), ButtonState::from_winit(state)))
       }
-      winit::event::WindowEvent::ScaleFactorChanged { .. } => Self::Window(WindowEvent::Rescaled),
+      winit::event::WindowEvent::Ime(ime) => Self::Input(InputEvent::Ime(ime.into())),
+      winit::event::WindowEvent::Touch(touch) => Self::Input(InputEvent::Touch(touch.into())),
+      winit::event::WindowEvent::TouchpadMagnify { delta, .. } => Self::Input(InputEvent::TouchpadMagnify { delta }),
+      winit::event::WindowEvent::TouchpadRotate { delta, .. } => {
+        Self::Input(InputEvent::TouchpadRotate { degrees: delta })
+      }
+      winit::event::WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+        Self::Window(WindowEvent::Rescaled { scale_factor })
+      }
       _ => Self::Window(WindowEvent::Unmapped(value)),
     }
   }
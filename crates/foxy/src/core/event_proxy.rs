@@ -0,0 +1,71 @@
+use crossbeam::channel::Sender;
+use winit::event_loop::EventLoopProxy;
+
+use super::{message::LoopEvent, FoxyResult};
+use crate::foxy_error;
+
+#[derive(Debug)]
+enum Target<T: 'static> {
+  EventLoop(EventLoopProxy<LoopEvent<T>>),
+  Channel(Sender<T>),
+}
+
+/// Posts user events to a running app from any thread. They reach
+/// [`Runnable::user_event`](super::runnable::Runnable::user_event) in the
+/// order they were sent, before the next frame's update. Sending also wakes
+/// the event loop and the game thread, so it works with
+/// [`Polling::Wait`](super::builder::Polling) and `Polling::Reactive`.
+///
+/// Get one from [`State::event_proxy`](super::foxy_state::State::event_proxy)
+/// and clone it into background jobs:
+///
+/// ```ignore
+/// let proxy = foxy.read().event_proxy::<Self::UserEvent>().unwrap();
+/// std::thread::spawn(move || {
+///   let bytes = download();
+///   let _ = proxy.send(Message::Downloaded(bytes));
+/// });
+/// ```
+#[derive(Debug)]
+pub struct EventProxy<T: 'static> {
+  target: Target<T>,
+}
+
+impl<T: 'static> EventProxy<T> {
+  pub(crate) fn from_event_loop(proxy: EventLoopProxy<LoopEvent<T>>) -> Self {
+    Self {
+      target: Target::EventLoop(proxy),
+    }
+  }
+
+  pub(crate) fn from_channel(sender: Sender<T>) -> Self {
+    Self {
+      target: Target::Channel(sender),
+    }
+  }
+
+  /// Fails once the app has shut down.
+  pub fn send(&self, event: T) -> FoxyResult<()> {
+    let sent = match &self.target {
+      Target::EventLoop(proxy) => proxy.send_event(LoopEvent::User(event)).is_ok(),
+      Target::Channel(sender) => sender.send(event).is_ok(),
+    };
+
+    if sent {
+      Ok(())
+    } else {
+      Err(foxy_error!("cannot send user event, the app has shut down"))
+    }
+  }
+}
+
+impl<T: 'static> Clone for EventProxy<T> {
+  fn clone(&self) -> Self {
+    let target = match &self.target {
+      Target::EventLoop(proxy) => Target::EventLoop(proxy.clone()),
+      Target::Channel(sender) => Target::Channel(sender.clone()),
+    };
+
+    Self { target }
+  }
+}
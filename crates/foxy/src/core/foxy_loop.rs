@@ -1,6 +1,15 @@
-use std::{sync::Arc, thread::JoinHandle, time::Duration};
+use std::{
+  panic::{self, AssertUnwindSafe},
+  sync::{Arc, Mutex},
+  thread::JoinHandle,
+  time::{Duration, Instant},
+};
 
-use crossbeam::{channel::TryRecvError, queue::ArrayQueue};
+use crossbeam::{
+  channel::{RecvTimeoutError, TryRecvError},
+  queue::ArrayQueue,
+};
+use egui::{FullOutput, RawInput, ViewportId};
 use foxy_renderer::{
   error::RendererError,
   renderer::{render_data::RenderData, Renderer},
@@ -8,36 +17,50 @@ use foxy_renderer::{
 use foxy_utils::{
   log::LogErr,
   mailbox::{Mailbox, MessagingError},
-  time::{timer::Timer, EngineTime},
+  time::{
+    pacer::{FramePacer, Pace},
+    timer::Timer,
+    EngineTime,
+  },
 };
 use tracing::*;
 use winit::{
-  event::{Event, KeyEvent, WindowEvent},
-  event_loop::{ControlFlow, EventLoop, EventLoopWindowTarget},
+  dpi::PhysicalSize,
+  event::{DeviceEvent, Event, WindowEvent},
+  event_loop::{ControlFlow, EventLoop, EventLoopProxy, EventLoopWindowTarget},
   window::Window,
 };
 
 use super::{
-  builder::{DebugInfo, FoxyCreateInfo, Polling},
+  builder::{DebugInfo, FoxyCreateInfo, LifecyclePolicy, Polling},
   runnable::Runnable,
   FoxyResult,
 };
 use crate::core::{
-  event::FoxyEvent,
+  event::{self, FoxyEvent, InputEvent},
+  event_proxy::EventProxy,
   foxy_state::{self, Foxy},
-  message::{GameLoopMessage, RenderLoopMessage},
+  headless::Headless,
+  input::{
+    gamepad::{GamepadBackend, Gamepads},
+    logical::LogicalKey,
+  },
+  message::{GameLoopMessage, LoopEvent, RenderLoopMessage},
   runnable::Flow,
+  schedule::{Phase, Schedule},
+  window_command::WindowCommand,
   FoxyError,
 };
 
-struct State {
+struct State<T: 'static + Send + Sync> {
   polling_strategy: Polling,
+  frame_pacer: Option<FramePacer>,
   debug_info: DebugInfo,
 
   renderer: Renderer,
   render_time: EngineTime,
   render_queue: Arc<ArrayQueue<RenderData>>,
-  render_mailbox: Mailbox<RenderLoopMessage, GameLoopMessage>,
+  render_mailbox: Mailbox<RenderLoopMessage<T>, GameLoopMessage>,
 
   // Keep window below the renderer to ensure proper drop order
   window: Arc<Window>,
@@ -46,25 +69,47 @@ struct State {
 
   original_title: String,
   fps_timer: Timer,
+  /// Why the app is shutting down, if it isn't a normal exit.
+  error: Option<FoxyError>,
   had_first_frame: bool,
+
+  suspended: bool,
+  occluded: bool,
+  minimized: bool,
+  hidden: bool,
+}
+
+/// How often the game thread runs, from [`FoxyCreateInfo`].
+struct GamePacing {
+  update_limit: Option<u32>,
+  lifecycle: LifecyclePolicy,
+  /// Tell the main thread about each frame, since it won't poll for them.
+  reactive: bool,
 }
 
 pub struct Framework<T: 'static + Send + Sync> {
-  state: Option<State>,
-  event_loop: EventLoop<T>,
+  state: Option<State<T>>,
+  event_loop: EventLoop<LoopEvent<T>>,
 }
 
 impl Framework<()> {
-  pub fn new<App: Runnable>(create_info: FoxyCreateInfo) -> FoxyResult<Self> {
+  pub fn new<App: Runnable<UserEvent = ()>>(create_info: FoxyCreateInfo) -> FoxyResult<Self> {
     Self::with_events::<App>(create_info)
   }
+
+  /// Runs `App` without a window or renderer. See [`Headless`].
+  pub fn headless<App: Runnable>(create_info: FoxyCreateInfo) -> Headless<App> {
+    Headless::new(create_info)
+  }
 }
 
 impl<T: 'static + Send + Sync> Framework<T> {
   const GAME_THREAD_ID: &'static str = "foxy";
   const MAX_FRAME_DATA_IN_FLIGHT: usize = 2;
 
-  pub fn with_events<App: Runnable>(create_info: FoxyCreateInfo) -> FoxyResult<Self> {
+  /// Like [`new`](Framework::new), for apps with a [`Runnable::UserEvent`]
+  /// type.
+  pub fn with_events<App: Runnable<UserEvent = T>>(create_info: FoxyCreateInfo) -> FoxyResult<Self> {
     trace!("Firing up Foxy");
 
     let (event_loop, window) = create_info.window.create_window()?;
@@ -74,16 +119,49 @@ impl<T: 'static + Send + Sync> Framework<T> {
     let render_queue = Arc::new(ArrayQueue::new(Self::MAX_FRAME_DATA_IN_FLIGHT));
 
     let foxy = Foxy::new(foxy_state::State::new(time, window.clone()));
+    {
+      let mut state = foxy.write();
+      *state.input.actions_mut() = create_info.actions;
+      state.set_event_proxy(EventProxy::from_event_loop(event_loop.create_proxy()));
+
+      let proxy = Mutex::new(event_loop.create_proxy());
+      state.set_window_commands(move |command| {
+        let proxy = proxy.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        proxy.send_event(LoopEvent::Command(command)).is_ok()
+      });
+
+      let proxy = Mutex::new(event_loop.create_proxy());
+      state.set_wake(move || {
+        let proxy = proxy.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let _ = proxy.send_event(LoopEvent::Wake);
+      });
+    }
     let egui_context = foxy.read().egui_context.clone();
     let (game_mailbox, render_mailbox) = Mailbox::new_entangled_pair();
-    let game_thread = Some(Self::game_loop::<App>(game_mailbox, foxy, render_queue.clone())?);
-
-    let renderer = Renderer::new(window.clone(), egui_context)?;
+    let game_thread = Some(Self::game_loop::<App>(
+      game_mailbox,
+      foxy,
+      render_queue.clone(),
+      create_info.gamepads,
+      create_info.schedule,
+      GamePacing {
+        update_limit: create_info.update_limit,
+        lifecycle: create_info.lifecycle,
+        reactive: create_info.polling_strategy == Polling::Reactive,
+      },
+      event_loop.create_proxy(),
+    )?);
+
+    let renderer = Renderer::new(window.clone(), egui_context, create_info.vsync)?;
     let render_time = create_info.time.build();
 
     Ok(Self {
       state: Some(State {
         polling_strategy: create_info.polling_strategy,
+        frame_pacer: match create_info.polling_strategy {
+          Polling::Limited { fps } => Some(FramePacer::new(fps.into())),
+          Polling::Poll | Polling::Wait | Polling::Reactive => None,
+        },
         debug_info: create_info.debug_info,
         renderer,
         render_time,
@@ -93,27 +171,35 @@ impl<T: 'static + Send + Sync> Framework<T> {
         window,
         game_thread,
         fps_timer: Timer::new(),
+        error: None,
         had_first_frame: false,
+        suspended: false,
+        occluded: false,
+        minimized: false,
+        hidden: false,
       }),
       event_loop,
     })
   }
 
+  /// Runs until the window closes or the game thread stops. Errors returned
+  /// from [`Runnable`] hooks, panics on the game thread and renderer failures
+  /// are returned here once both threads have shut down.
   pub fn run(self) -> FoxyResult<()> {
     info!("KON KON KITSUNE!");
     let Some(mut state) = self.state else {
-      return Err(FoxyError::Error(format!("failed to take foxy state")));
+      return Err(FoxyError::Error("failed to take foxy state".to_string()));
     };
 
     let _ = state.render_mailbox.send(RenderLoopMessage::Start).log_error();
 
-    self.event_loop.set_control_flow(match state.polling_strategy {
-      Polling::Poll => ControlFlow::Poll,
-      Polling::Wait => ControlFlow::Wait,
-    });
+    self
+      .event_loop
+      .set_control_flow(Self::control_flow(state.polling_strategy));
 
-    Ok(self.event_loop.run(move |event, elwt| {
-      let _ = &state; // ensure state is moved
+    let mut outcome = Ok(());
+    self.event_loop.run(|event, elwt| {
+      let state = &mut state;
 
       match event {
         Event::WindowEvent { event, .. } => {
@@ -130,12 +216,17 @@ impl<T: 'static + Send + Sync> Framework<T> {
                 elwt.exit();
               }
             }
-            WindowEvent::Resized(_) | WindowEvent::ScaleFactorChanged { .. } => {
+            WindowEvent::Resized(size) => Self::resized(state, elwt, size),
+            WindowEvent::ScaleFactorChanged { .. } => {
               state.renderer.refresh();
               state.window.request_redraw();
             }
+            WindowEvent::Occluded(occluded) => {
+              state.occluded = occluded;
+              Self::update_visibility(state, elwt);
+            }
             WindowEvent::RedrawRequested => {
-              Self::render(&mut state, elwt);
+              Self::render(state, elwt);
             }
             _ => (),
           }
@@ -143,30 +234,160 @@ impl<T: 'static + Send + Sync> Framework<T> {
           if !elwt.exiting() {
             // !was_handled went here
             if let Err(error) = state.render_mailbox.send(RenderLoopMessage::Winit(event)) {
-              error!("{error:?}")
+              error!("{error}")
+            }
+          }
+        }
+        Event::DeviceEvent {
+          event: DeviceEvent::MouseMotion { delta },
+          ..
+        } => {
+          if let Err(error) = state.render_mailbox.send(RenderLoopMessage::MouseMotion(delta)) {
+            error!("{error}")
+          }
+        }
+        Event::UserEvent(LoopEvent::User(event)) => {
+          if let Err(error) = state.render_mailbox.send(RenderLoopMessage::UserEvent(event)) {
+            error!("{error}")
+          }
+        }
+        Event::UserEvent(LoopEvent::Wake) => {
+          if let Err(error) = state.render_mailbox.send(RenderLoopMessage::Wake) {
+            error!("{error}")
+          }
+        }
+        Event::UserEvent(LoopEvent::FrameReady) => {
+          state.window.request_redraw();
+        }
+        Event::UserEvent(LoopEvent::Command(command)) => {
+          if let Some(event) = Self::apply_window_command(state, command) {
+            // Some platforms resize right away instead of sending an event
+            if let FoxyEvent::Window(event::WindowEvent::Resized { physical, .. }) = event {
+              Self::resized(state, elwt, physical);
+            }
+            if let Err(error) = state.render_mailbox.send(RenderLoopMessage::Event(event)) {
+              error!("{error}")
             }
           }
         }
+        Event::Suspended => {
+          state.suspended = true;
+          Self::update_visibility(state, elwt);
+        }
+        // Also sent once at startup, when there is nothing to restore
+        Event::Resumed if state.suspended => {
+          state.suspended = false;
+          if let Err(error) = state.renderer.recreate_surface() {
+            error!("failed to recreate surface: {error}");
+          }
+          Self::update_visibility(state, elwt);
+        }
         Event::AboutToWait => {
           // redraw
           if !state.had_first_frame {
-            Self::render(&mut state, elwt);
-          } else {
+            Self::render(state, elwt);
+          } else if state.hidden {
+            elwt.set_control_flow(ControlFlow::Wait);
+          } else if let Some(pacer) = &mut state.frame_pacer {
+            let wake = match pacer.poll() {
+              Pace::Ready => {
+                state.window.request_redraw();
+                pacer.wake_time()
+              }
+              Pace::WaitUntil(wake) => wake,
+            };
+            elwt.set_control_flow(ControlFlow::WaitUntil(wake));
+          } else if state.polling_strategy != Polling::Reactive {
             state.window.request_redraw();
           }
         }
+        Event::UserEvent(LoopEvent::GameExited) => {
+          elwt.exit();
+        }
         Event::LoopExiting => {
-          if let Some(thread) = state.game_thread.take() {
-            let _ = thread.join();
-          }
+          let game = match state.game_thread.take().map(JoinHandle::join) {
+            Some(Ok(result)) => result,
+            Some(Err(payload)) => Err(FoxyError::from_panic(payload)),
+            None => Ok(()),
+          };
+
+          // The game's own error comes first, since renderer errors are often fallout
+          // from it
+          outcome = match (game, state.error.take()) {
+            (Err(error), _) | (Ok(()), Some(error)) => {
+              error!("{error}");
+              Err(error)
+            }
+            (Ok(()), None) => Ok(()),
+          };
           info!("OTSU KON DESHITA!");
         }
         _ => (),
       }
-    })?)
+    })?;
+
+    outcome
+  }
+
+  fn control_flow(polling_strategy: Polling) -> ControlFlow {
+    match polling_strategy {
+      Polling::Poll => ControlFlow::Poll,
+      Polling::Wait | Polling::Limited { .. } | Polling::Reactive => ControlFlow::Wait,
+    }
+  }
+
+  /// Tells the game thread when the window is hidden or shown again. Waits
+  /// for the first frame, since the window isn't made visible before then.
+  fn update_visibility(state: &mut State<T>, elwt: &EventLoopWindowTarget<LoopEvent<T>>) {
+    let hidden = state.suspended || state.occluded || state.minimized;
+    if !state.had_first_frame || hidden == state.hidden {
+      return;
+    }
+    state.hidden = hidden;
+
+    let message = if hidden {
+      RenderLoopMessage::Hidden
+    } else {
+      elwt.set_control_flow(Self::control_flow(state.polling_strategy));
+      state.renderer.refresh();
+      state.window.request_redraw();
+      RenderLoopMessage::Shown
+    };
+
+    if let Err(error) = state.render_mailbox.send(message) {
+      error!("{error}")
+    }
+  }
+
+  fn resized(state: &mut State<T>, elwt: &EventLoopWindowTarget<LoopEvent<T>>, size: PhysicalSize<u32>) {
+    state.minimized = size.width == 0 || size.height == 0;
+    Self::update_visibility(state, elwt);
+    state.renderer.refresh();
+    state.window.request_redraw();
+  }
+
+  fn apply_window_command(state: &mut State<T>, command: WindowCommand) -> Option<FoxyEvent> {
+    if let WindowCommand::SetTitle(title) = &command {
+      state.original_title.clone_from(title);
+    }
+
+    match command.clone().apply(&state.window) {
+      Ok(event) => event.map(FoxyEvent::Window),
+      Err(error) => {
+        warn!("{command:?} failed: {error}");
+        Some(FoxyEvent::Window(event::WindowEvent::CommandFailed {
+          command,
+          reason: error.to_string(),
+        }))
+      }
+    }
   }
 
-  fn render(state: &mut State, elwt: &EventLoopWindowTarget<T>) {
+  fn render(state: &mut State<T>, elwt: &EventLoopWindowTarget<LoopEvent<T>>) {
+    if state.hidden {
+      return;
+    }
+
     let render_data = state.render_queue.pop();
     let Some(render_data) = render_data else {
       return;
@@ -181,6 +402,7 @@ impl<T: 'static + Send + Sync> Framework<T> {
       Ok(()) if !state.had_first_frame => {
         state.had_first_frame = true;
         state.window.set_visible(true);
+        Self::update_visibility(state, elwt);
       }
       Err(RendererError::RebuildSwapchain) => {
         state.renderer.refresh();
@@ -188,6 +410,7 @@ impl<T: 'static + Send + Sync> Framework<T> {
       Err(error) => {
         error!("`{error}` Aborting...");
         let _ = state.render_mailbox.send_and_recv(RenderLoopMessage::MustExit);
+        state.error = Some(error.into());
         elwt.exit();
       }
       _ => (),
@@ -204,127 +427,376 @@ impl<T: 'static + Send + Sync> Framework<T> {
     }
   }
 
-  fn game_loop<App: Runnable>(
-    mailbox: Mailbox<GameLoopMessage, RenderLoopMessage>,
+  fn game_loop<App: Runnable<UserEvent = T>>(
+    mailbox: Mailbox<GameLoopMessage, RenderLoopMessage<T>>,
     foxy: Foxy,
     render_queue: Arc<ArrayQueue<RenderData>>,
+    gamepads: Gamepads,
+    mut schedule: Schedule,
+    pacing: GamePacing,
+    proxy: EventLoopProxy<LoopEvent<T>>,
   ) -> FoxyResult<JoinHandle<FoxyResult<()>>> {
+    let GamePacing {
+      update_limit,
+      lifecycle,
+      reactive,
+    } = pacing;
+
     let handle = std::thread::Builder::new()
       .name(Self::GAME_THREAD_ID.into())
       .spawn(move || -> FoxyResult<()> {
-        let _ = mailbox.recv().log_error();
-        let window = foxy.read().window.clone();
-
-        let mut app = App::new(&foxy);
-        app.start(&foxy);
-        loop {
-          let next_message = mailbox.try_recv();
-
-          let raw_input = foxy.write().take_egui_input();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| -> FoxyResult<()> {
+          let _ = mailbox.recv().log_error();
+
+          let mut gamepads = gamepads.into_backend();
+          let mut pacer = update_limit.map(|limit| FramePacer::new(limit.into()));
+          schedule.build_plugins(&foxy)?;
+          let mut app = App::new(&foxy);
+          app.start(&foxy)?;
+          schedule.start_plugins(&foxy)?;
+
+          let mut events = Vec::new();
+          let mut fixed_events = Vec::new();
+          // Draw the first frame without waiting for input
+          let mut repaint_at = Some(Instant::now());
+          let mut hidden = false;
+          'game: loop {
+            if foxy.read().take_exit_request() {
+              schedule.stop_plugins(&foxy);
+              app.delete();
+              break 'game;
+            }
 
-          let event = match next_message {
-            Ok(RenderLoopMessage::MustExit) => {
-              let _ = mailbox.send(GameLoopMessage::Exit);
+            if foxy.read().take_restart_request() {
               app.stop(&foxy);
-              app.delete();
-              break;
+              app = restart_app(&foxy, app, &mut schedule)?;
+              events.clear();
+              fixed_events.clear();
             }
-            Ok(RenderLoopMessage::ExitRequested) => {
-              if let Flow::Exit = app.stop(&foxy) {
-                let _ = mailbox.send(GameLoopMessage::Exit);
-                app.delete();
-                break;
-              } else {
-                let _ = mailbox.send(GameLoopMessage::DontExit);
-              }
-              None
+
+            let mut woken_by = None;
+            if hidden && lifecycle != LifecyclePolicy::KeepUpdating {
+              woken_by = Some(mailbox.recv().map_err(|_| TryRecvError::Disconnected.into()));
+            } else if reactive {
+              woken_by = sleep_until_needed(&mailbox, &foxy, repaint_at);
             }
-            Ok(RenderLoopMessage::Winit(event)) => {
-              let was_handled = foxy.write().handle_input(&event);
 
-              if !was_handled {
-                match event {
-                  WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
-                    foxy.write().egui_context.set_zoom_factor(scale_factor as f32);
+            // Drain everything the main thread sent since the last frame so input doesn't
+            // lag behind.
+            loop {
+              match woken_by.take().unwrap_or_else(|| mailbox.try_recv()) {
+                Ok(RenderLoopMessage::MustExit) => {
+                  let _ = mailbox.send(GameLoopMessage::Exit);
+                  app.stop(&foxy);
+                  schedule.stop_plugins(&foxy);
+                  app.delete();
+                  break 'game;
+                }
+                Ok(RenderLoopMessage::ExitRequested) => match app.stop(&foxy) {
+                  Flow::Exit => {
+                    let _ = mailbox.send(GameLoopMessage::Exit);
+                    schedule.stop_plugins(&foxy);
+                    app.delete();
+                    break 'game;
                   }
-                  WindowEvent::KeyboardInput {
-                    event:
-                      KeyEvent {
-                        physical_key,
-                        state: element_state,
-                        repeat,
-                        ..
-                      },
-                    ..
-                  } => {
-                    foxy.write().input.update_key_state(physical_key, element_state, repeat);
+                  Flow::Restart => {
+                    let _ = mailbox.send(GameLoopMessage::DontExit);
+                    app = restart_app(&foxy, app, &mut schedule)?;
+                    events.clear();
+                    fixed_events.clear();
                   }
-                  WindowEvent::MouseInput {
-                    button,
-                    state: element_state,
-                    ..
-                  } => {
-                    foxy.write().input.update_mouse_button_state(button, element_state);
+                  _ => {
+                    let _ = mailbox.send(GameLoopMessage::DontExit);
                   }
-                  WindowEvent::ModifiersChanged(mods) => {
-                    foxy.write().input.update_modifiers_state(mods);
+                },
+                Ok(RenderLoopMessage::Winit(event)) => {
+                  handle_window_event(&foxy, event, &mut events);
+                }
+                Ok(RenderLoopMessage::UserEvent(event)) => {
+                  app.user_event(&foxy, event)?;
+                }
+                Ok(RenderLoopMessage::Event(event)) => {
+                  events.push(event);
+                }
+                Ok(RenderLoopMessage::Hidden) => {
+                  hidden = true;
+                  app.suspended(&foxy)?;
+                  if lifecycle == LifecyclePolicy::Pause {
+                    foxy.write().engine_time.pause();
                   }
-                  _ => (),
                 }
-
-                Some(event)
-              } else {
-                None
+                Ok(RenderLoopMessage::Shown) => {
+                  hidden = false;
+                  foxy.write().engine_time.resume();
+                  app.resumed(&foxy)?;
+                }
+                Ok(RenderLoopMessage::MouseMotion(delta)) => {
+                  foxy.write().input.update_mouse_motion(delta);
+                  events.push(FoxyEvent::Input(InputEvent::MouseMotion { delta }));
+                }
+                Ok(_) => (),
+                Err(MessagingError::TryRecvError {
+                  error: TryRecvError::Disconnected,
+                }) => {
+                  app.stop(&foxy);
+                  schedule.stop_plugins(&foxy);
+                  app.delete();
+                  break 'game;
+                }
+                Err(_) => break,
               }
             }
-            Err(MessagingError::TryRecvError {
-              error: TryRecvError::Disconnected,
-            }) => {
-              app.stop(&foxy);
-              app.delete();
-              break;
+
+            // Keep collecting events while asleep so none are lost
+            if hidden && lifecycle != LifecyclePolicy::KeepUpdating {
+              continue;
             }
-            _ => None,
-          };
 
-          // Loop
+            poll_input(&foxy, gamepads.as_deref_mut(), &mut events);
 
-          let event = FoxyEvent::from(event);
+            let raw_input = foxy.write().take_egui_input();
+            foxy.read().clear_due_redraw(Instant::now());
+            let full_output = run_frame(&foxy, &mut app, &mut schedule, &events, &mut fixed_events, raw_input)?;
+            events.clear();
 
-          // let raw_input = foxy.write().egui_state.take_egui_input(&window);
+            repaint_at = full_output
+              .viewport_output
+              .get(&ViewportId::ROOT)
+              .and_then(|viewport| Instant::now().checked_add(viewport.repaint_delay));
+            render_queue.force_push(RenderData { full_output });
 
-          foxy.write().engine_time.update();
-          while foxy.write().engine_time.should_do_tick_unchecked() {
-            foxy.write().engine_time.tick();
-            app.fixed_update(&foxy, &event);
-          }
+            if reactive {
+              let _ = proxy.send_event(LoopEvent::FrameReady);
+            }
 
-          if let FoxyEvent::Input(event) = &event {
-            app.input(&foxy, event);
+            if let Some(pacer) = &mut pacer {
+              pacer.wait();
+            }
           }
 
-          app.update(&foxy, &event);
+          // debug!("BAU BAU FOR NOW");
+          Ok(())
+        }));
 
-          if let FoxyEvent::Window(event) = &event {
-            app.window(&foxy, event);
-          }
+        // However the game ended, let the main thread shut the render side down
+        let _ = proxy.send_event(LoopEvent::GameExited);
+        result.unwrap_or_else(|payload| Err(FoxyError::from_panic(payload)))
+      })?;
 
-          let full_output = foxy.read().egui_context.run(raw_input, |ui| {
-            app.gui(&foxy, ui);
-          });
+    Ok(handle)
+  }
+}
 
-          foxy
-            .write()
-            .egui_state
-            .handle_platform_output(&window, full_output.platform_output.clone());
+/// Blocks a [`Polling::Reactive`] game thread until the next frame is
+/// needed: a message arrives, a redraw requested with
+/// [`State::request_redraw_after`](foxy_state::State::request_redraw_after)
+/// is due, or egui wants to repaint at `repaint_at`. Returns the message that
+/// woke it, if any.
+fn sleep_until_needed<T: Send + Sync>(
+  mailbox: &Mailbox<GameLoopMessage, RenderLoopMessage<T>>,
+  foxy: &Foxy,
+  repaint_at: Option<Instant>,
+) -> Option<Result<RenderLoopMessage<T>, MessagingError<GameLoopMessage>>> {
+  /// Most idle time counted towards the next frame's delta.
+  const MAX_IDLE: Duration = Duration::from_millis(100);
+
+  let deadline = match (foxy.read().redraw_at(), repaint_at) {
+    (Some(a), Some(b)) => Some(a.min(b)),
+    (a, b) => a.or(b),
+  };
+
+  let message = match deadline {
+    Some(deadline) if deadline <= Instant::now() => return None,
+    Some(deadline) => mailbox.recv_deadline(deadline),
+    None => mailbox.recv(),
+  };
+
+  let message = match message {
+    Ok(message) => Some(Ok(message)),
+    Err(MessagingError::RecvTimeoutError {
+      error: RecvTimeoutError::Timeout,
+    }) => None,
+    // Anything else means the main thread hung up
+    Err(_) => Some(Err(TryRecvError::Disconnected.into())),
+  };
+
+  foxy.write().engine_time.clamp_idle(MAX_IDLE);
+  message
+}
 
-          render_queue.force_push(RenderData { full_output });
-        }
+/// Lets egui see `event` first and updates [`Input`](super::input::Input)
+/// with whatever egui didn't consume. Events that should reach the app are
+/// converted and pushed onto `events`.
+fn handle_window_event(foxy: &Foxy, event: WindowEvent, events: &mut Vec<FoxyEvent>) {
+  let was_handled = foxy.write().handle_input(&event);
+  if was_handled {
+    return;
+  }
 
-        // debug!("BAU BAU FOR NOW");
-        Ok(())
-      })?;
+  let mut gestures = Vec::new();
+  match event {
+    WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+      let mut state = foxy.write();
+      state.egui_context.set_zoom_factor(scale_factor as f32);
+      state.input.update_scale_factor(scale_factor);
+    }
+    WindowEvent::CursorMoved { position, .. } => {
+      foxy.write().input.update_cursor_position(position);
+    }
+    WindowEvent::CursorLeft { .. } => {
+      foxy.write().input.clear_cursor_position();
+    }
+    WindowEvent::MouseWheel { delta, .. } => {
+      foxy.write().input.update_scroll(delta.into());
+    }
+    WindowEvent::KeyboardInput { ref event, .. } => {
+      foxy.write().input.update_key_state(
+        event.physical_key,
+        LogicalKey::from_key_event(event),
+        event.state,
+        event.repeat,
+      );
+    }
+    WindowEvent::MouseInput {
+      button,
+      state: element_state,
+      ..
+    } => {
+      foxy.write().input.update_mouse_button_state(button, element_state);
+    }
+    WindowEvent::ModifiersChanged(mods) => {
+      foxy.write().input.update_modifiers_state(mods);
+    }
+    WindowEvent::Touch(touch) => {
+      gestures = foxy.write().input.update_touch(touch.into());
+    }
+    WindowEvent::TouchpadMagnify { delta, .. } => {
+      gestures = foxy.write().input.update_touchpad_magnify(delta);
+    }
+    WindowEvent::TouchpadRotate { delta, .. } => {
+      gestures = foxy.write().input.update_touchpad_rotate(delta);
+    }
+    _ => (),
+  }
 
-    Ok(handle)
+  let text = match &event {
+    WindowEvent::KeyboardInput { event, .. } => FoxyEvent::text_from_winit(event),
+    _ => None,
+  };
+
+  events.push(FoxyEvent::from_winit(event, &foxy.read().input));
+  events.extend(text);
+  events.extend(
+    gestures
+      .into_iter()
+      .map(|gesture| FoxyEvent::Input(InputEvent::Gesture(gesture))),
+  );
+}
+
+/// Collects input that doesn't arrive as a window event: gamepads from
+/// `gamepads`, and gestures that are recognized by time alone. Both update
+/// [`Input`](super::input::Input) and are pushed onto `events`.
+pub(crate) fn poll_input(
+  foxy: &Foxy,
+  gamepads: Option<&mut (dyn GamepadBackend + 'static)>,
+  events: &mut Vec<FoxyEvent>,
+) {
+  let mut polled = Vec::new();
+  if let Some(gamepads) = gamepads {
+    gamepads.poll(&mut polled);
+  }
+
+  let mut state = foxy.write();
+  for event in polled {
+    state.input.update_gamepad(&event);
+    events.push(FoxyEvent::Input(event));
+  }
+
+  let gestures = state.input.poll_gestures();
+  events.extend(
+    gestures
+      .into_iter()
+      .map(|gesture| FoxyEvent::Input(InputEvent::Gesture(gesture))),
+  );
+}
+
+/// Deletes `app` and builds a fresh one in its place, keeping the window,
+/// renderer and egui. Callers have already asked the old app to
+/// [`stop`](Runnable::stop). Shared by the game thread and [`Headless`].
+pub(crate) fn restart_app<App: Runnable>(foxy: &Foxy, app: App, schedule: &mut Schedule) -> FoxyResult<App> {
+  debug!("restarting");
+  schedule.stop_plugins(foxy);
+  app.delete();
+
+  foxy.write().reset();
+  foxy.world().clear();
+
+  let mut app = App::new(foxy);
+  app.start(foxy)?;
+  schedule.start_plugins(foxy)?;
+  Ok(app)
+}
+
+/// Runs a single frame of the game loop once incoming messages have been
+/// handled, going through each [`Phase`] in order. Shared by the game thread
+/// and [`Headless`].
+///
+/// `events` are this frame's events, in the order they arrived. They are also
+/// appended to `fixed_events`, which is handed to the first fixed tick and
+/// then cleared, so every event reaches `fixed_update` exactly once even on
+/// frames that run zero or several ticks.
+pub(crate) fn run_frame<App: Runnable>(
+  foxy: &Foxy,
+  app: &mut App,
+  schedule: &mut Schedule,
+  events: &[FoxyEvent],
+  fixed_events: &mut Vec<FoxyEvent>,
+  raw_input: RawInput,
+) -> FoxyResult<FullOutput> {
+  fixed_events.extend_from_slice(events);
+  foxy.write().engine_time.update();
+  foxy.world().begin_frame();
+
+  for event in events {
+    if let FoxyEvent::Input(event) = event {
+      app.input(foxy, event)?;
+    }
   }
+  schedule.run(Phase::PreUpdate, foxy, events)?;
+
+  while foxy.write().engine_time.should_do_tick_unchecked() {
+    foxy.write().engine_time.tick();
+    foxy.write().input.begin_fixed_tick();
+    app.fixed_update(foxy, fixed_events)?;
+    schedule.run(Phase::Fixed, foxy, fixed_events)?;
+    foxy.write().input.end_fixed_tick();
+    fixed_events.clear();
+  }
+
+  app.update(foxy, events)?;
+  for event in events {
+    if let FoxyEvent::Window(event) = event {
+      app.window(foxy, event)?;
+    }
+  }
+  schedule.run(Phase::Update, foxy, events)?;
+
+  app.late_update(foxy, events)?;
+  schedule.run(Phase::LateUpdate, foxy, events)?;
+
+  // Cloned so hooks and systems can lock `foxy` while egui runs
+  let egui = foxy.read().egui_context.clone();
+  let mut gui = Ok(());
+  let full_output = egui.run(raw_input, |ui| {
+    gui = app.gui(foxy, ui).and_then(|()| schedule.run(Phase::Gui, foxy, events));
+  });
+  gui?;
+
+  schedule.run(Phase::PreRender, foxy, events)?;
+
+  let mut state = foxy.write();
+  state.handle_platform_output(full_output.platform_output.clone());
+  state.input.end_frame();
+
+  Ok(full_output)
 }
@@ -1,41 +1,128 @@
-use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::{
+  any::Any,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+    Mutex,
+    RwLock,
+    RwLockReadGuard,
+    RwLockWriteGuard,
+  },
+  time::{Duration, Instant},
+};
 
-use egui::{epaint::Shadow, style::HandleShape, Context, RawInput, Rounding, Visuals};
+use egui::{epaint::Shadow, Context, PlatformOutput, RawInput, Rounding, Visuals};
 use foxy_utils::time::{EngineTime, Time};
-use winit::{event::WindowEvent, window::Window};
+use tracing::*;
+use winit::{
+  dpi::{Position, Size},
+  event::WindowEvent,
+  window::Window,
+};
 
-use super::input::Input;
+use super::{
+  clipboard::Clipboard,
+  ecs::World,
+  event_proxy::EventProxy,
+  input::Input,
+  resources::{Res, ResMut, Resources},
+  window_command::WindowCommand,
+};
 
 #[derive(Clone)]
-pub struct Foxy(Arc<RwLock<State>>);
+pub struct Foxy {
+  state: Arc<RwLock<State>>,
+  resources: Arc<Resources>,
+  world: Arc<World>,
+}
 
 impl Foxy {
   pub fn new(state: State) -> Self {
-    Self(Arc::new(RwLock::new(state)))
+    Self {
+      state: Arc::new(RwLock::new(state)),
+      resources: Arc::default(),
+      world: Arc::default(),
+    }
+  }
+
+  pub fn read(&self) -> RwLockReadGuard<'_, State> {
+    self.state.read().expect("reader panicked")
   }
 
-  pub fn read(&self) -> RwLockReadGuard<State> {
-    self.0.read().expect("reader panicked")
+  pub fn write(&self) -> RwLockWriteGuard<'_, State> {
+    self.state.write().expect("reader panicked")
   }
 
-  pub fn write(&self) -> RwLockWriteGuard<State> {
-    self.0.write().expect("reader panicked")
+  /// Adds a resource, replacing and returning the previous one of the same
+  /// type. See [`Resources`].
+  pub fn insert_resource<T: Send + Sync + 'static>(&self, value: T) -> Option<T> {
+    self.resources.insert(value)
+  }
+
+  pub fn resource<T: Send + Sync + 'static>(&self) -> Option<Res<T>> {
+    self.resources.get()
+  }
+
+  pub fn resource_mut<T: Send + Sync + 'static>(&self) -> Option<ResMut<T>> {
+    self.resources.get_mut()
+  }
+
+  pub fn has_resource<T: Send + Sync + 'static>(&self) -> bool {
+    self.resources.contains::<T>()
+  }
+
+  /// Takes the resource of type `T` out, if there is one. See
+  /// [`Resources::remove`].
+  pub fn remove_resource<T: Send + Sync + 'static>(&self) -> Option<T> {
+    self.resources.remove::<T>()
+  }
+
+  pub fn resources(&self) -> &Resources {
+    &self.resources
+  }
+
+  /// The entities and components of the app. See [`World`].
+  pub fn world(&self) -> &World {
+    &self.world
   }
 }
 
 pub struct State {
   pub(crate) engine_time: EngineTime,
-  pub(crate) window: Arc<Window>,
+  pub(crate) window: Option<Arc<Window>>,
   pub(crate) egui_context: Context,
-  pub(crate) egui_state: egui_winit::State,
+  // egui-winit's clipboard isn't `Sync` on every platform (e.g. Wayland)
+  pub(crate) egui_state: Option<Mutex<egui_winit::State>>,
   pub(crate) input: Input,
+  clipboard: Clipboard,
+  // An `EventProxy<App::UserEvent>`; erased since `State` isn't generic over the app
+  event_proxy: Option<Mutex<Box<dyn Any + Send>>>,
+  window_commands: Option<Box<dyn Fn(WindowCommand) -> bool + Send + Sync>>,
+  /// Earliest pending [`State::request_redraw_after`] deadline.
+  redraw_at: Mutex<Option<Instant>>,
+  wake: Option<Box<dyn Fn() + Send + Sync>>,
+  exit_requested: AtomicBool,
+  restart_requested: AtomicBool,
 }
 
 impl State {
   pub fn new(engine_time: EngineTime, window: Arc<Window>) -> Self {
-    let egui_context = Context::default();
+    let mut state = Self::headless(engine_time);
+
+    let id = state.egui_context.viewport_id();
+    let egui_state = egui_winit::State::new(state.egui_context.clone(), id, &window, None, None);
 
-    let id = egui_context.viewport_id();
+    state.input.update_scale_factor(window.scale_factor());
+    state.clipboard = Clipboard::native();
+    state.window = Some(window);
+    state.egui_state = Some(Mutex::new(egui_state));
+    state
+  }
+
+  /// State without a window or egui platform integration. egui still runs,
+  /// but only ever sees default input.
+  pub fn headless(engine_time: EngineTime) -> Self {
+    let egui_context = Context::default();
 
     const BORDER_RADIUS: f32 = 6.0;
 
@@ -48,14 +135,21 @@ impl State {
 
     egui_context.set_visuals(visuals);
 
-    let egui_state = egui_winit::State::new(egui_context.clone(), id, &window, None, None);
+    let input = Input::with_clock(engine_time.clock().clone());
 
     Self {
       engine_time,
-      window,
+      window: None,
       egui_context,
-      egui_state,
-      input: Input::new(),
+      egui_state: None,
+      input,
+      clipboard: Clipboard::memory(),
+      event_proxy: None,
+      window_commands: None,
+      redraw_at: Mutex::new(None),
+      wake: None,
+      exit_requested: AtomicBool::new(false),
+      restart_requested: AtomicBool::new(false),
     }
   }
 
@@ -63,16 +157,165 @@ impl State {
     self.engine_time.time()
   }
 
-  pub fn window(&self) -> &Arc<Window> {
-    &self.window
+  pub fn is_headless(&self) -> bool {
+    self.window.is_none()
+  }
+
+  /// The egui context, for drawing from systems in
+  /// [`Phase::Gui`](super::schedule::Phase::Gui).
+  pub fn egui(&self) -> &Context {
+    &self.egui_context
   }
 
   pub fn input(&self) -> &Input {
     &self.input
   }
 
+  /// Mutable access for configuration such as
+  /// [`Input::actions_mut`]. Button state itself is only updated by the
+  /// framework.
+  pub fn input_mut(&mut self) -> &mut Input {
+    &mut self.input
+  }
+
+  /// The system clipboard. Headless runs get a clipboard of their own that
+  /// other apps can't see.
+  pub fn clipboard(&self) -> Clipboard {
+    self.clipboard.clone()
+  }
+
+  /// A proxy for posting
+  /// [`Runnable::UserEvent`](super::runnable::Runnable::UserEvent)s
+  /// to the app. `None` if `T` isn't the app's user event type.
+  pub fn event_proxy<T: 'static>(&self) -> Option<EventProxy<T>> {
+    let proxy = self.event_proxy.as_ref()?.lock().ok()?;
+    proxy.downcast_ref::<EventProxy<T>>().cloned()
+  }
+
+  pub(crate) fn set_event_proxy<T: Send + 'static>(&mut self, proxy: EventProxy<T>) {
+    self.event_proxy = Some(Mutex::new(Box::new(proxy)));
+  }
+
+  /// Asks the main thread to change the window. Ignored when running
+  /// headless.
+  pub fn send_window_command(&self, command: WindowCommand) {
+    match &self.window_commands {
+      Some(send) => {
+        if !send(command) {
+          debug!("window command dropped, the event loop has exited");
+        }
+      }
+      None => trace!("ignoring {command:?} without a window"),
+    }
+  }
+
+  /// Enables or disables IME. While enabled, composed text arrives as
+  /// [`ImeEvent`](super::event::ImeEvent)s instead of plain key text.
+  pub fn set_ime_allowed(&self, allowed: bool) {
+    self.send_window_command(WindowCommand::SetImeAllowed(allowed));
+  }
+
+  /// Where the text being edited is on screen, so the IME can place its
+  /// candidate window next to it.
+  pub fn set_ime_cursor_area(&self, position: impl Into<Position>, size: impl Into<Size>) {
+    self.send_window_command(WindowCommand::SetImeCursorArea {
+      position: position.into(),
+      size: size.into(),
+    });
+  }
+
+  /// Asks for another frame as soon as possible. Only needed with
+  /// [`Polling::Reactive`](super::builder::Polling), where frames otherwise
+  /// only follow input. Safe to call from any thread.
+  pub fn request_redraw(&self) {
+    self.request_redraw_after(Duration::ZERO);
+  }
+
+  /// Asks for another frame after `delay`, e.g. to blink a text cursor. Only
+  /// the earliest pending request is kept.
+  pub fn request_redraw_after(&self, delay: Duration) {
+    let at = Instant::now() + delay;
+    let mut redraw_at = self.redraw_at.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if redraw_at.is_some_and(|current| current <= at) {
+      return;
+    }
+
+    *redraw_at = Some(at);
+    drop(redraw_at);
+    if let Some(wake) = &self.wake {
+      wake();
+    }
+  }
+
+  /// Ends the game once the current frame is done, as if the window had been
+  /// closed, but without asking
+  /// [`Runnable::stop`](super::runnable::Runnable::stop). Safe to call from
+  /// any thread.
+  pub fn request_exit(&self) {
+    self.exit_requested.store(true, Ordering::Release);
+    if let Some(wake) = &self.wake {
+      wake();
+    }
+  }
+
+  /// Clears a pending [`request_exit`](Self::request_exit), returning whether
+  /// there was one.
+  pub(crate) fn take_exit_request(&self) -> bool {
+    self.exit_requested.swap(false, Ordering::AcqRel)
+  }
+
+  /// Replaces the app with a fresh one once the current frame is done, e.g.
+  /// to restart a level or apply settings. The old app gets
+  /// [`Runnable::stop`](super::runnable::Runnable::stop), whose answer is
+  /// ignored, and is deleted before the new one is created with
+  /// [`Runnable::new`](super::runnable::Runnable::new).
+  ///
+  /// Time and input start over and the [`World`](super::ecs::World) is
+  /// cleared. The window, renderer, egui, resources and plugins are kept.
+  /// Safe to call from any thread.
+  pub fn request_restart(&self) {
+    self.restart_requested.store(true, Ordering::Release);
+    if let Some(wake) = &self.wake {
+      wake();
+    }
+  }
+
+  pub(crate) fn take_restart_request(&self) -> bool {
+    self.restart_requested.swap(false, Ordering::AcqRel)
+  }
+
+  /// Starts time and input over for a fresh app.
+  pub(crate) fn reset(&mut self) {
+    self.engine_time.reset();
+    self.input.reset();
+  }
+
+  pub(crate) fn redraw_at(&self) -> Option<Instant> {
+    *self.redraw_at.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+  }
+
+  /// Forgets the pending redraw request if it is due by `now`.
+  pub(crate) fn clear_due_redraw(&self, now: Instant) {
+    let mut redraw_at = self.redraw_at.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if redraw_at.is_some_and(|at| at <= now) {
+      *redraw_at = None;
+    }
+  }
+
+  pub(crate) fn set_wake(&mut self, wake: impl Fn() + Send + Sync + 'static) {
+    self.wake = Some(Box::new(wake));
+  }
+
+  pub(crate) fn set_window_commands(&mut self, send: impl Fn(WindowCommand) -> bool + Send + Sync + 'static) {
+    self.window_commands = Some(Box::new(send));
+  }
+
   pub(crate) fn handle_input(&mut self, event: &WindowEvent) -> bool {
-    let response = self.egui_state.on_window_event(&self.window, event);
+    let Some((window, egui_state)) = self.platform() else {
+      return false;
+    };
+
+    let response = egui_state.on_window_event(window, event);
 
     if response.repaint {
       self.egui_context.request_repaint();
@@ -82,6 +325,22 @@ impl State {
   }
 
   pub(crate) fn take_egui_input(&mut self) -> RawInput {
-    self.egui_state.take_egui_input(&self.window)
+    match self.platform() {
+      Some((window, egui_state)) => egui_state.take_egui_input(window),
+      None => RawInput::default(),
+    }
+  }
+
+  pub(crate) fn handle_platform_output(&mut self, platform_output: PlatformOutput) {
+    if let Some((window, egui_state)) = self.platform() {
+      egui_state.handle_platform_output(window, platform_output);
+    }
+  }
+
+  fn platform(&mut self) -> Option<(&Arc<Window>, &mut egui_winit::State)> {
+    match (&self.window, &mut self.egui_state) {
+      (Some(window), Some(egui_state)) => Some((window, egui_state.get_mut().expect("egui state poisoned"))),
+      _ => None,
+    }
   }
 }
@@ -0,0 +1,259 @@
+use std::{sync::Arc, time::Duration};
+
+use crossbeam::channel::Receiver;
+use foxy_utils::time::TimeCreateInfo;
+use quanta::{Clock, Mock};
+use tracing::*;
+
+use super::{
+  builder::FoxyCreateInfo,
+  event::FoxyEvent,
+  event_proxy::EventProxy,
+  foxy_loop::{poll_input, restart_app, run_frame},
+  foxy_state::{self, Foxy},
+  input::gamepad::GamepadBackend,
+  runnable::{Flow, Runnable},
+  schedule::Schedule,
+  FoxyResult,
+};
+
+/// Drives a [`Runnable`] on the calling thread without a window, GPU or egui
+/// platform integration. Frames are stepped explicitly, which makes this
+/// suitable for dedicated servers and for integration tests.
+///
+/// ```ignore
+/// let mut app = Framework::headless::<App>(FoxyCreateInfo::default()).with_manual_clock();
+/// app.advance(Duration::from_millis(16));
+/// app.step()?;
+/// ```
+pub struct Headless<App: Runnable> {
+  foxy: Foxy,
+  app: Option<App>,
+  exited: bool,
+  time: TimeCreateInfo,
+  mock: Option<Arc<Mock>>,
+  gamepads: Option<Box<dyn GamepadBackend>>,
+  schedule: Schedule,
+  user_events: Receiver<App::UserEvent>,
+  event_proxy: EventProxy<App::UserEvent>,
+  events: Vec<FoxyEvent>,
+  fixed_events: Vec<FoxyEvent>,
+}
+
+impl<App: Runnable> Headless<App> {
+  pub fn new(create_info: FoxyCreateInfo) -> Self {
+    trace!("Firing up headless Foxy");
+
+    let foxy = Foxy::new(foxy_state::State::headless(create_info.time.build()));
+    let (sender, user_events) = crossbeam::channel::unbounded();
+    let event_proxy = EventProxy::from_channel(sender);
+    {
+      let mut state = foxy.write();
+      *state.input.actions_mut() = create_info.actions;
+      state.set_event_proxy(event_proxy.clone());
+    }
+
+    Self {
+      foxy,
+      app: None,
+      exited: false,
+      time: create_info.time,
+      mock: None,
+      gamepads: create_info.gamepads.into_backend(),
+      schedule: create_info.schedule,
+      user_events,
+      event_proxy,
+      events: Vec::new(),
+      fixed_events: Vec::new(),
+    }
+  }
+
+  /// Replaces the real-time clock with one that only moves when
+  /// [`advance`](Self::advance) is called. Must be called before the app
+  /// starts.
+  pub fn with_manual_clock(mut self) -> Self {
+    if self.app.is_some() {
+      warn!("cannot switch to a manual clock after the app has started");
+      return self;
+    }
+
+    let (clock, mock) = Clock::mock();
+    {
+      let mut state = self.foxy.write();
+      state.input.set_clock(clock.clone());
+      state.engine_time = self.time.build_with_clock(clock);
+    }
+    self.mock = Some(mock);
+    self
+  }
+
+  pub fn foxy(&self) -> &Foxy {
+    &self.foxy
+  }
+
+  /// `None` before [`start`](Self::start) and once the app has exited.
+  pub fn app(&self) -> Option<&App> {
+    self.app.as_ref()
+  }
+
+  pub fn app_mut(&mut self) -> Option<&mut App> {
+    self.app.as_mut()
+  }
+
+  pub fn is_running(&self) -> bool {
+    self.app.is_some()
+  }
+
+  /// `true` once the app has been deleted, either through
+  /// [`State::request_exit`](foxy_state::State::request_exit) or a
+  /// [`stop`](Self::stop) that returned [`Flow::Exit`]. An exited app is never
+  /// started again.
+  pub fn has_exited(&self) -> bool {
+    self.exited
+  }
+
+  /// Moves the manual clock forward. Does nothing when using the real clock.
+  pub fn advance(&mut self, delta: Duration) {
+    match &self.mock {
+      Some(mock) => mock.increment(delta),
+      None => warn!("advance() requires a manual clock"),
+    }
+  }
+
+  /// The same proxy the app gets from
+  /// [`State::event_proxy`](super::foxy_state::State::event_proxy). User
+  /// events are delivered at the start of the next [`step`](Self::step).
+  pub fn event_proxy(&self) -> EventProxy<App::UserEvent> {
+    self.event_proxy.clone()
+  }
+
+  /// Queues an event for the app's hooks on the next [`step`](Self::step).
+  pub fn push_event(&mut self, event: FoxyEvent) {
+    self.events.push(event);
+  }
+
+  /// Constructs the app and calls [`Runnable::start`]. Called implicitly by
+  /// the first [`step`](Self::step). Does nothing if the app is already
+  /// running or has exited.
+  pub fn start(&mut self) -> FoxyResult<()> {
+    if self.app.is_none() && !self.exited {
+      self.schedule.build_plugins(&self.foxy)?;
+      let mut app = App::new(&self.foxy);
+      app.start(&self.foxy)?;
+      self.schedule.start_plugins(&self.foxy)?;
+      self.app = Some(app);
+    }
+
+    Ok(())
+  }
+
+  /// Runs one frame: as many fixed ticks as the clock allows, then `update`
+  /// and `gui`. Returns the first error from the app's hooks. Afterwards the
+  /// app is deleted if it called
+  /// [`State::request_exit`](foxy_state::State::request_exit), or replaced
+  /// if it called
+  /// [`State::request_restart`](foxy_state::State::request_restart).
+  ///
+  /// Does nothing once the app has exited.
+  pub fn step(&mut self) -> FoxyResult<()> {
+    self.start()?;
+
+    let Some(app) = self.app.as_mut() else {
+      return Ok(());
+    };
+
+    for event in self.user_events.try_iter() {
+      app.user_event(&self.foxy, event)?;
+    }
+
+    poll_input(&self.foxy, self.gamepads.as_deref_mut(), &mut self.events);
+
+    let raw_input = self.foxy.write().take_egui_input();
+    let frame = run_frame(
+      &self.foxy,
+      app,
+      &mut self.schedule,
+      &self.events,
+      &mut self.fixed_events,
+      raw_input,
+    );
+    self.events.clear();
+
+    frame?;
+
+    if self.foxy.read().take_exit_request() {
+      self.delete_app();
+    } else if self.foxy.read().take_restart_request() {
+      if let Some(app) = self.app.as_mut() {
+        app.stop(&self.foxy);
+      }
+      self.restart()?;
+    }
+
+    Ok(())
+  }
+
+  /// Asks the app to stop, as a window close would. The app is deleted if it
+  /// returns [`Flow::Exit`] and replaced if it returns [`Flow::Restart`].
+  /// Returns the error if the replacement fails to start.
+  pub fn stop(&mut self) -> FoxyResult<Flow> {
+    let Some(app) = self.app.as_mut() else {
+      return Ok(Flow::Exit);
+    };
+
+    let flow = app.stop(&self.foxy);
+    match flow {
+      Flow::Exit => self.delete_app(),
+      Flow::Restart => self.restart()?,
+      _ => (),
+    }
+
+    Ok(flow)
+  }
+
+  /// Replaces a running app with a fresh one. See
+  /// [`State::request_restart`](foxy_state::State::request_restart).
+  fn restart(&mut self) -> FoxyResult<()> {
+    if let Some(app) = self.app.take() {
+      self.events.clear();
+      self.fixed_events.clear();
+      self.app = Some(restart_app(&self.foxy, app, &mut self.schedule)?);
+    }
+
+    Ok(())
+  }
+
+  fn delete_app(&mut self) {
+    if let Some(app) = self.app.take() {
+      self.schedule.stop_plugins(&self.foxy);
+      app.delete();
+      self.exited = true;
+    }
+  }
+
+  /// Steps frames for as long as `should_continue` returns `true`, then stops
+  /// the app. Bails out on the first error from the app's hooks.
+  ///
+  /// The app may veto the stop by returning [`Flow::Continue`] or
+  /// [`Flow::Restart`]. Stepping then carries on only if `should_continue`
+  /// returns `true` again; otherwise this returns with the app still running.
+  pub fn run_while(&mut self, mut should_continue: impl FnMut(&Foxy, &App) -> bool) -> FoxyResult<()> {
+    self.start()?;
+
+    let mut vetoed = false;
+    while let Some(app) = &self.app {
+      if should_continue(&self.foxy, app) {
+        vetoed = false;
+      } else if vetoed || self.stop()?.is_exit() {
+        break;
+      } else {
+        vetoed = true;
+        continue;
+      }
+
+      self.step()?;
+    }
+
+    Ok(())
+  }
+}
@@ -1,39 +1,89 @@
+pub mod action;
+pub mod button;
+pub mod gamepad;
+pub mod gesture;
 pub mod key;
+pub mod logical;
 pub mod modifier;
 pub mod mouse;
 pub mod state;
+pub mod touch;
 
-use std::collections::HashMap;
+use std::{
+  collections::{hash_map::Entry, HashMap},
+  time::Duration,
+};
 
+use quanta::Clock;
 use strum::IntoEnumIterator;
 use winit::{
+  dpi::{LogicalPosition, PhysicalPosition},
   event::{ElementState, MouseButton},
   keyboard::PhysicalKey,
 };
 
-use self::state::KeyState;
-use crate::core::input::{key::KeyCode, mouse::MouseCode, state::ButtonState};
+use self::{
+  action::ActionMap,
+  button::{Button, ButtonTracker},
+  gamepad::{GamepadAxis, GamepadButton, GamepadId, GamepadSettings, Stick},
+  gesture::{Gesture, GestureRecognizer, GestureSettings},
+  logical::LogicalKey,
+  modifier::Modifier,
+  mouse::ScrollDelta,
+  state::KeyState,
+  touch::{Touch, TouchPhase},
+};
+use crate::core::{
+  event::InputEvent,
+  input::{key::KeyCode, mouse::MouseCode, state::ButtonState},
+};
 
+/// Keyboard, mouse, modifier, gamepad and touch state as of the current frame.
+///
+/// Cursor deltas, raw mouse motion and scrolling are summed over the frame and
+/// reset when it ends, as is the list of recognized gestures.
+///
+/// `just_pressed` and `just_released` report the edges seen since the previous
+/// frame. Inside `fixed_update` they instead report the edges seen since the
+/// previous fixed tick, so presses are never missed on frames without a tick.
 #[derive(Debug)]
 pub struct Input {
-  mouse_buttons: HashMap<MouseCode, ButtonState>,
+  clock: Clock,
+  in_fixed_tick: bool,
   keys: HashMap<KeyCode, KeyState>,
-  shift: ButtonState,
-  ctrl: ButtonState,
-  alt: ButtonState,
-  win: ButtonState,
+  pub(crate) key_buttons: ButtonTracker<KeyCode>,
+  pub(crate) logical_keys: ButtonTracker<LogicalKey>,
+  /// The logical key each held physical key pressed, so the release matches
+  /// even if the layout changed in between.
+  held_logical: HashMap<KeyCode, LogicalKey>,
+  /// The last logical key seen for each physical key, used for labels.
+  layout: HashMap<KeyCode, LogicalKey>,
+  pub(crate) mouse_buttons: ButtonTracker<MouseCode>,
+  pub(crate) modifiers: ButtonTracker<Modifier>,
+  pub(crate) gamepad_buttons: ButtonTracker<(GamepadId, GamepadButton)>,
+  gamepads: HashMap<GamepadId, String>,
+  gamepad_axes: HashMap<(GamepadId, GamepadAxis), f32>,
+  gamepad_settings: GamepadSettings,
+  touches: HashMap<u64, Touch>,
+  gesture_recognizer: GestureRecognizer,
+  gestures: Vec<Gesture>,
+  actions: ActionMap,
+  scale_factor: f64,
+  cursor: Option<PhysicalPosition<f64>>,
+  cursor_delta: (f64, f64),
+  mouse_motion: (f64, f64),
+  scroll_lines: (f32, f32),
+  scroll_pixels: (f64, f64),
 }
 
 impl Input {
   pub fn new() -> Self {
-    let mouse_buttons = {
-      let mut map = HashMap::default();
-      for code in MouseCode::iter() {
-        map.insert(code, ButtonState::Released);
-      }
-      map
-    };
+    Self::with_clock(Clock::new())
+  }
 
+  /// Uses `clock` to time how long buttons are held, so that durations follow
+  /// the engine clock.
+  pub fn with_clock(clock: Clock) -> Self {
     let keys = {
       let mut map = HashMap::default();
       for code in KeyCode::iter() {
@@ -43,81 +93,492 @@ impl Input {
     };
 
     Self {
-      mouse_buttons,
+      clock,
+      in_fixed_tick: false,
       keys,
-      shift: Default::default(),
-      ctrl: Default::default(),
-      alt: Default::default(),
-      win: Default::default(),
+      key_buttons: Default::default(),
+      logical_keys: Default::default(),
+      held_logical: HashMap::new(),
+      layout: HashMap::new(),
+      mouse_buttons: Default::default(),
+      modifiers: Default::default(),
+      gamepad_buttons: Default::default(),
+      gamepads: HashMap::new(),
+      gamepad_axes: HashMap::new(),
+      gamepad_settings: Default::default(),
+      touches: HashMap::new(),
+      gesture_recognizer: Default::default(),
+      gestures: Vec::new(),
+      actions: Default::default(),
+      scale_factor: 1.0,
+      cursor: None,
+      cursor_delta: (0.0, 0.0),
+      mouse_motion: (0.0, 0.0),
+      scroll_lines: (0.0, 0.0),
+      scroll_pixels: (0.0, 0.0),
     }
   }
 
+  // BUTTONS
+
+  pub fn pressed<B: Button>(&self, button: B) -> bool {
+    B::tracker(self).is_pressed(&button)
+  }
+
+  /// Pressed since the previous frame (or fixed tick, inside `fixed_update`).
+  pub fn just_pressed<B: Button>(&self, button: B) -> bool {
+    B::tracker(self).just_pressed(&button, self.in_fixed_tick)
+  }
+
+  /// Released since the previous frame (or fixed tick, inside
+  /// `fixed_update`).
+  pub fn just_released<B: Button>(&self, button: B) -> bool {
+    B::tracker(self).just_released(&button, self.in_fixed_tick)
+  }
+
+  /// Whether `button` is down and has been for at least `duration`.
+  pub fn held_for<B: Button>(&self, button: B, duration: Duration) -> bool {
+    self.held_duration(button).is_some_and(|held| held >= duration)
+  }
+
+  /// How long `button` has been down, or `None` if it's released.
+  pub fn held_duration<B: Button>(&self, button: B) -> Option<Duration> {
+    B::tracker(self).held_for(&button, self.clock.now())
+  }
+
+  // ACTIONS
+
+  pub fn actions(&self) -> &ActionMap {
+    &self.actions
+  }
+
+  pub fn actions_mut(&mut self) -> &mut ActionMap {
+    &mut self.actions
+  }
+
+  /// Whether any chord bound to `action` is held.
+  pub fn action(&self, action: &str) -> bool {
+    self.actions.bindings(action).iter().any(|chord| chord.pressed(self))
+  }
+
+  pub fn action_just_pressed(&self, action: &str) -> bool {
+    self
+      .actions
+      .bindings(action)
+      .iter()
+      .any(|chord| chord.just_pressed(self))
+  }
+
+  pub fn action_just_released(&self, action: &str) -> bool {
+    self
+      .actions
+      .bindings(action)
+      .iter()
+      .any(|chord| chord.just_released(self))
+  }
+
+  /// Value of a 1D axis in `[-1, 1]`, or `0.0` if `axis` isn't bound.
+  pub fn axis(&self, axis: &str) -> f32 {
+    self.actions.axis(axis).map_or(0.0, |axis| axis.value(self))
+  }
+
+  /// Value of a 2D axis within the unit circle, or `(0.0, 0.0)` if `axis`
+  /// isn't bound.
+  pub fn axis2(&self, axis: &str) -> (f32, f32) {
+    self.actions.axis2(axis).map_or((0.0, 0.0), |axis| axis.value(self))
+  }
+
   // KEYBOARD
 
   pub fn key(&self, code: KeyCode) -> KeyState {
     self.keys.get(&code).copied().unwrap_or(KeyState::Released)
   }
 
-  pub(crate) fn update_key_state(&mut self, keycode: PhysicalKey, state: ElementState, repeat: bool) {
-    if let Some(key_state) = self.keys.get_mut(&keycode.into()) {
+  /// The logical key last produced by the physical key `code` under the
+  /// current layout, or `None` if it hasn't been pressed yet.
+  pub fn logical_key(&self, code: KeyCode) -> Option<&LogicalKey> {
+    self.layout.get(&code)
+  }
+
+  /// What to call `code` in the UI, e.g. "Z" for `KeyCode::W` on AZERTY.
+  /// Falls back to the US layout name until the key has been pressed.
+  pub fn key_label(&self, code: KeyCode) -> String {
+    match self.logical_key(code) {
+      Some(LogicalKey::Unidentified) | None => format!("{code:?}").trim_start_matches('_').to_owned(),
+      Some(key) => key.label(),
+    }
+  }
+
+  pub(crate) fn update_key_state(
+    &mut self,
+    keycode: PhysicalKey,
+    logical: LogicalKey,
+    state: ElementState,
+    repeat: bool,
+  ) {
+    let code = keycode.into();
+    if let Some(key_state) = self.keys.get_mut(&code) {
       *key_state = KeyState::from_winit(state, repeat);
     }
+
+    let now = self.clock.now();
+    self.key_buttons.set(code, state.is_pressed(), now);
+
+    if state.is_pressed() {
+      if let Entry::Vacant(entry) = self.held_logical.entry(code) {
+        entry.insert(logical.clone());
+        self.logical_keys.press(logical.clone(), now);
+      }
+      self.layout.insert(code, logical);
+    } else {
+      let pressed = self.held_logical.remove(&code).unwrap_or(logical);
+      self.logical_keys.release(pressed);
+    }
   }
 
   // MOUSE
 
   pub fn mouse(&self, code: MouseCode) -> ButtonState {
-    self.mouse_buttons.get(&code).copied().unwrap_or(ButtonState::Released)
+    Self::button_state(self.pressed(code))
   }
 
   pub(crate) fn update_mouse_button_state(&mut self, button: MouseButton, state: ElementState) {
-    if let Some(mouse_state) = self.mouse_buttons.get_mut(&button.into()) {
-      *mouse_state = ButtonState::from_winit(state);
+    self
+      .mouse_buttons
+      .set(button.into(), state.is_pressed(), self.clock.now());
+  }
+
+  /// Last known cursor position in physical pixels, relative to the top-left
+  /// of the window. `None` until the cursor first moves over the window, and
+  /// again once it leaves.
+  pub fn cursor_position(&self) -> Option<PhysicalPosition<f64>> {
+    self.cursor
+  }
+
+  pub fn cursor_position_logical(&self) -> Option<LogicalPosition<f64>> {
+    self.cursor.map(|position| position.to_logical(self.scale_factor))
+  }
+
+  /// How far the cursor moved this frame, in physical pixels.
+  pub fn cursor_delta(&self) -> (f64, f64) {
+    self.cursor_delta
+  }
+
+  /// Raw device motion this frame. Unaffected by pointer acceleration and the
+  /// window edges, which makes it the one to use for camera look.
+  pub fn mouse_motion(&self) -> (f64, f64) {
+    self.mouse_motion
+  }
+
+  /// Wheel scrolling this frame, in lines.
+  pub fn scroll_lines(&self) -> (f32, f32) {
+    self.scroll_lines
+  }
+
+  /// Touchpad scrolling this frame, in physical pixels.
+  pub fn scroll_pixels(&self) -> (f64, f64) {
+    self.scroll_pixels
+  }
+
+  pub fn scale_factor(&self) -> f64 {
+    self.scale_factor
+  }
+
+  pub(crate) fn update_cursor_position(&mut self, position: PhysicalPosition<f64>) {
+    if let Some(previous) = self.cursor {
+      self.cursor_delta.0 += position.x - previous.x;
+      self.cursor_delta.1 += position.y - previous.y;
+    }
+    self.cursor = Some(position);
+  }
+
+  /// Forgets the cursor position, so that the jump to wherever it comes back
+  /// in doesn't count towards [`cursor_delta`](Self::cursor_delta).
+  pub(crate) fn clear_cursor_position(&mut self) {
+    self.cursor = None;
+  }
+
+  pub(crate) fn update_mouse_motion(&mut self, (x, y): (f64, f64)) {
+    self.mouse_motion.0 += x;
+    self.mouse_motion.1 += y;
+  }
+
+  pub(crate) fn update_scroll(&mut self, delta: ScrollDelta) {
+    match delta {
+      ScrollDelta::Lines { x, y } => {
+        self.scroll_lines.0 += x;
+        self.scroll_lines.1 += y;
+      }
+      ScrollDelta::Pixels { x, y } => {
+        self.scroll_pixels.0 += x;
+        self.scroll_pixels.1 += y;
+      }
+    }
+  }
+
+  pub(crate) fn update_scale_factor(&mut self, scale_factor: f64) {
+    self.scale_factor = scale_factor;
+  }
+
+  // GAMEPADS
+
+  /// Connected gamepads, in no particular order.
+  pub fn gamepads(&self) -> impl Iterator<Item = GamepadId> + '_ {
+    self.gamepads.keys().copied()
+  }
+
+  pub fn gamepad_name(&self, id: GamepadId) -> Option<&str> {
+    self.gamepads.get(&id).map(String::as_str)
+  }
+
+  pub fn gamepad_settings(&self) -> &GamepadSettings {
+    &self.gamepad_settings
+  }
+
+  pub fn gamepad_settings_mut(&mut self) -> &mut GamepadSettings {
+    &mut self.gamepad_settings
+  }
+
+  /// Whether `button` is held on any connected gamepad.
+  pub fn any_gamepad_pressed(&self, button: GamepadButton) -> bool {
+    self.gamepads().any(|id| self.pressed((id, button)))
+  }
+
+  pub fn any_gamepad_just_pressed(&self, button: GamepadButton) -> bool {
+    self.gamepads().any(|id| self.just_pressed((id, button)))
+  }
+
+  pub fn any_gamepad_just_released(&self, button: GamepadButton) -> bool {
+    // The gamepad may have disconnected since, so look at the edges directly.
+    self
+      .gamepad_buttons
+      .just_released_any(|(_, released)| *released == button, self.in_fixed_tick)
+  }
+
+  /// Value of `axis` on gamepad `id` with dead zones applied. Sticks use a
+  /// radial dead zone, see [`gamepad_stick`](Self::gamepad_stick).
+  pub fn gamepad_axis(&self, id: GamepadId, axis: GamepadAxis) -> f32 {
+    match axis.stick() {
+      Some(stick) => {
+        let (x, y) = self.gamepad_stick(id, stick);
+        if axis == stick.axes().0 {
+          x
+        } else {
+          y
+        }
+      }
+      None => self
+        .gamepad_settings
+        .trigger_dead_zone
+        .apply(self.raw_gamepad_axis(id, axis).clamp(0.0, 1.0)),
     }
   }
 
+  /// Position of a stick within the unit circle, with the stick dead zone
+  /// applied to its length.
+  pub fn gamepad_stick(&self, id: GamepadId, stick: Stick) -> (f32, f32) {
+    let (x_axis, y_axis) = stick.axes();
+    let raw = (self.raw_gamepad_axis(id, x_axis), self.raw_gamepad_axis(id, y_axis));
+    let (x, y) = self.gamepad_settings.stick_dead_zone.apply_radial(raw);
+    let length = (x * x + y * y).sqrt();
+    if length > 1.0 {
+      (x / length, y / length)
+    } else {
+      (x, y)
+    }
+  }
+
+  /// The value of `axis` furthest from rest across all connected gamepads.
+  pub fn any_gamepad_axis(&self, axis: GamepadAxis) -> f32 {
+    self
+      .gamepads()
+      .map(|id| self.gamepad_axis(id, axis))
+      .fold(0.0, |strongest, value| {
+        if value.abs() > strongest.abs() {
+          value
+        } else {
+          strongest
+        }
+      })
+  }
+
+  fn raw_gamepad_axis(&self, id: GamepadId, axis: GamepadAxis) -> f32 {
+    self.gamepad_axes.get(&(id, axis)).copied().unwrap_or(0.0)
+  }
+
+  /// Applies a gamepad event from a backend. Other events are ignored.
+  pub(crate) fn update_gamepad(&mut self, event: &InputEvent) {
+    match event {
+      InputEvent::GamepadConnected { id, name } => {
+        self.gamepads.insert(*id, name.clone());
+      }
+      InputEvent::GamepadDisconnected { id } => {
+        let held: Vec<_> = self
+          .gamepad_buttons
+          .held()
+          .filter(|(pad, _)| pad == id)
+          .copied()
+          .collect();
+        for button in held {
+          self.gamepad_buttons.release(button);
+        }
+        self.gamepad_axes.retain(|(pad, _), _| pad != id);
+        self.gamepads.remove(id);
+      }
+      InputEvent::GamepadButton { id, button, state } => {
+        self
+          .gamepad_buttons
+          .set((*id, *button), state.is_pressed(), self.clock.now());
+      }
+      InputEvent::GamepadAxis { id, axis, value } => {
+        self.gamepad_axes.insert((*id, *axis), *value);
+      }
+      _ => (),
+    }
+  }
+
+  // TOUCH
+
+  /// Fingers currently on the screen.
+  pub fn touches(&self) -> impl Iterator<Item = &Touch> {
+    self.touches.values()
+  }
+
+  pub fn touch(&self, id: u64) -> Option<&Touch> {
+    self.touches.get(&id)
+  }
+
+  /// Gestures recognized this frame, in order. Each is also sent as an
+  /// [`InputEvent::Gesture`].
+  pub fn gestures(&self) -> &[Gesture] {
+    &self.gestures
+  }
+
+  pub fn gesture_settings(&self) -> &GestureSettings {
+    self.gesture_recognizer.settings()
+  }
+
+  pub fn gesture_settings_mut(&mut self) -> &mut GestureSettings {
+    self.gesture_recognizer.settings_mut()
+  }
+
+  /// Tracks `touch` and returns any gestures it completed.
+  pub(crate) fn update_touch(&mut self, touch: Touch) -> Vec<Gesture> {
+    match touch.phase {
+      TouchPhase::Started | TouchPhase::Moved => {
+        self.touches.insert(touch.id, touch);
+      }
+      TouchPhase::Ended | TouchPhase::Cancelled => {
+        self.touches.remove(&touch.id);
+      }
+    }
+
+    let now = self.clock.now();
+    self.recognize(|recognizer, gestures| recognizer.touch(&touch, now, gestures))
+  }
+
+  pub(crate) fn update_touchpad_magnify(&mut self, delta: f64) -> Vec<Gesture> {
+    self.recognize(|recognizer, gestures| recognizer.touchpad_magnify(delta, gestures))
+  }
+
+  pub(crate) fn update_touchpad_rotate(&mut self, degrees: f32) -> Vec<Gesture> {
+    self.recognize(|recognizer, gestures| recognizer.touchpad_rotate(degrees, gestures))
+  }
+
+  /// Returns gestures that are recognized by time alone, like long presses.
+  pub(crate) fn poll_gestures(&mut self) -> Vec<Gesture> {
+    let now = self.clock.now();
+    self.recognize(|recognizer, gestures| recognizer.poll(now, gestures))
+  }
+
+  fn recognize(&mut self, recognize: impl FnOnce(&mut GestureRecognizer, &mut Vec<Gesture>)) -> Vec<Gesture> {
+    let mut recognized = Vec::new();
+    recognize(&mut self.gesture_recognizer, &mut recognized);
+    self.gestures.extend_from_slice(&recognized);
+    recognized
+  }
+
   // MODS
 
   pub fn shift(&self) -> ButtonState {
-    self.shift
+    Self::button_state(self.pressed(Modifier::Shift))
   }
 
   pub fn ctrl(&self) -> ButtonState {
-    self.ctrl
+    Self::button_state(self.pressed(Modifier::Ctrl))
   }
 
   pub fn alt(&self) -> ButtonState {
-    self.alt
+    Self::button_state(self.pressed(Modifier::Alt))
   }
 
   pub fn win(&self) -> ButtonState {
-    self.win
+    Self::button_state(self.pressed(Modifier::Super))
   }
 
   pub(crate) fn update_modifiers_state(&mut self, modifiers: winit::event::Modifiers) {
-    self.shift = if modifiers.state().shift_key() {
-      ButtonState::Pressed
-    } else {
-      ButtonState::Released
-    };
+    let now = self.clock.now();
+    let state = modifiers.state();
 
-    self.ctrl = if modifiers.state().control_key() {
-      ButtonState::Pressed
-    } else {
-      ButtonState::Released
-    };
+    self.modifiers.set(Modifier::Shift, state.shift_key(), now);
+    self.modifiers.set(Modifier::Ctrl, state.control_key(), now);
+    self.modifiers.set(Modifier::Alt, state.alt_key(), now);
+    self.modifiers.set(Modifier::Super, state.super_key(), now);
+  }
 
-    self.alt = if modifiers.state().alt_key() {
-      ButtonState::Pressed
-    } else {
-      ButtonState::Released
-    };
+  // FRAME BOUNDARIES
+
+  /// Forgets everything held or in flight, e.g. so the key that triggered a
+  /// restart doesn't count as pressed afterwards. Bindings, settings, and
+  /// what is known about the window and connected gamepads stay.
+  pub(crate) fn reset(&mut self) {
+    let mut fresh = Self::with_clock(self.clock.clone());
+    fresh.actions = std::mem::take(&mut self.actions);
+    fresh.gamepad_settings = self.gamepad_settings;
+    *fresh.gesture_recognizer.settings_mut() = *self.gesture_recognizer.settings();
+    fresh.gamepads = std::mem::take(&mut self.gamepads);
+    fresh.layout = std::mem::take(&mut self.layout);
+    fresh.scale_factor = self.scale_factor;
+    fresh.cursor = self.cursor;
+    *self = fresh;
+  }
+
+  pub(crate) fn set_clock(&mut self, clock: Clock) {
+    self.clock = clock;
+  }
+
+  pub(crate) fn begin_fixed_tick(&mut self) {
+    self.in_fixed_tick = true;
+  }
+
+  pub(crate) fn end_fixed_tick(&mut self) {
+    self.in_fixed_tick = false;
+    self.key_buttons.end_tick();
+    self.logical_keys.end_tick();
+    self.mouse_buttons.end_tick();
+    self.modifiers.end_tick();
+    self.gamepad_buttons.end_tick();
+  }
+
+  pub(crate) fn end_frame(&mut self) {
+    self.key_buttons.end_frame();
+    self.logical_keys.end_frame();
+    self.mouse_buttons.end_frame();
+    self.modifiers.end_frame();
+    self.gamepad_buttons.end_frame();
+    self.cursor_delta = (0.0, 0.0);
+    self.mouse_motion = (0.0, 0.0);
+    self.scroll_lines = (0.0, 0.0);
+    self.scroll_pixels = (0.0, 0.0);
+    self.gestures.clear();
+  }
 
-    self.win = if modifiers.state().super_key() {
+  fn button_state(pressed: bool) -> ButtonState {
+    if pressed {
       ButtonState::Pressed
     } else {
       ButtonState::Released
-    };
+    }
   }
 }
 
@@ -126,3 +587,27 @@ impl Default for Input {
     Self::new()
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn reentering_the_window_does_not_jump() {
+    let mut input = Input::new();
+    input.update_cursor_position(PhysicalPosition::new(10.0, 10.0));
+    input.update_cursor_position(PhysicalPosition::new(15.0, 12.0));
+    assert_eq!(input.cursor_delta(), (5.0, 2.0));
+
+    input.clear_cursor_position();
+    assert_eq!(input.cursor_position(), None);
+
+    input.update_cursor_position(PhysicalPosition::new(500.0, 300.0));
+    assert_eq!(input.cursor_delta(), (5.0, 2.0));
+    assert_eq!(input.cursor_position(), Some(PhysicalPosition::new(500.0, 300.0)));
+
+    input.end_frame();
+    input.update_cursor_position(PhysicalPosition::new(501.0, 300.0));
+    assert_eq!(input.cursor_delta(), (1.0, 0.0));
+  }
+}
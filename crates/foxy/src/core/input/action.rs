@@ -0,0 +1,800 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+  gamepad::{GamepadAxis, GamepadButton},
+  key::KeyCode,
+  logical::LogicalKey,
+  modifier::Modifier,
+  mouse::MouseCode,
+  Input,
+};
+
+/// A single input that can drive an action or axis. `Key` follows the
+/// physical position of a key, `Logical` follows what the layout prints on it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Binding {
+  Key(KeyCode),
+  Logical(LogicalKey),
+  Mouse(MouseCode),
+  Modifier(Modifier),
+  /// The button on any connected gamepad.
+  Gamepad(GamepadButton),
+}
+
+impl Binding {
+  fn pressed(&self, input: &Input) -> bool {
+    match self {
+      Binding::Key(code) => input.pressed(*code),
+      Binding::Logical(key) => input.pressed(key.clone()),
+      Binding::Mouse(code) => input.pressed(*code),
+      Binding::Modifier(modifier) => input.pressed(*modifier),
+      Binding::Gamepad(button) => input.any_gamepad_pressed(*button),
+    }
+  }
+
+  fn just_pressed(&self, input: &Input) -> bool {
+    match self {
+      Binding::Key(code) => input.just_pressed(*code),
+      Binding::Logical(key) => input.just_pressed(key.clone()),
+      Binding::Mouse(code) => input.just_pressed(*code),
+      Binding::Modifier(modifier) => input.just_pressed(*modifier),
+      Binding::Gamepad(button) => input.any_gamepad_just_pressed(*button),
+    }
+  }
+
+  fn just_released(&self, input: &Input) -> bool {
+    match self {
+      Binding::Key(code) => input.just_released(*code),
+      Binding::Logical(key) => input.just_released(key.clone()),
+      Binding::Mouse(code) => input.just_released(*code),
+      Binding::Modifier(modifier) => input.just_released(*modifier),
+      Binding::Gamepad(button) => input.any_gamepad_just_released(*button),
+    }
+  }
+}
+
+impl From<KeyCode> for Binding {
+  fn from(value: KeyCode) -> Self {
+    Binding::Key(value)
+  }
+}
+
+impl From<LogicalKey> for Binding {
+  fn from(value: LogicalKey) -> Self {
+    Binding::Logical(value)
+  }
+}
+
+impl From<MouseCode> for Binding {
+  fn from(value: MouseCode) -> Self {
+    Binding::Mouse(value)
+  }
+}
+
+impl From<Modifier> for Binding {
+  fn from(value: Modifier) -> Self {
+    Binding::Modifier(value)
+  }
+}
+
+impl From<GamepadButton> for Binding {
+  fn from(value: GamepadButton) -> Self {
+    Binding::Gamepad(value)
+  }
+}
+
+/// A binding that only counts while all of its modifiers are held, e.g.
+/// <kbd>Ctrl</kbd>+<kbd>S</kbd>.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Chord {
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub modifiers: Vec<Modifier>,
+  pub binding: Binding,
+}
+
+impl Chord {
+  pub fn new(binding: impl Into<Binding>) -> Self {
+    Self {
+      modifiers: Vec::new(),
+      binding: binding.into(),
+    }
+  }
+
+  pub fn with(mut self, modifier: Modifier) -> Self {
+    self.modifiers.push(modifier);
+    self
+  }
+
+  pub fn pressed(&self, input: &Input) -> bool {
+    self.modifiers_held(input) && self.binding.pressed(input)
+  }
+
+  pub fn just_pressed(&self, input: &Input) -> bool {
+    self.modifiers_held(input) && self.binding.just_pressed(input)
+  }
+
+  pub fn just_released(&self, input: &Input) -> bool {
+    self.binding.just_released(input)
+  }
+
+  fn modifiers_held(&self, input: &Input) -> bool {
+    self.modifiers.iter().all(|modifier| input.pressed(*modifier))
+  }
+}
+
+impl From<Binding> for Chord {
+  fn from(value: Binding) -> Self {
+    Chord::new(value)
+  }
+}
+
+impl From<KeyCode> for Chord {
+  fn from(value: KeyCode) -> Self {
+    Chord::new(value)
+  }
+}
+
+impl From<LogicalKey> for Chord {
+  fn from(value: LogicalKey) -> Self {
+    Chord::new(value)
+  }
+}
+
+impl From<MouseCode> for Chord {
+  fn from(value: MouseCode) -> Self {
+    Chord::new(value)
+  }
+}
+
+impl From<Modifier> for Chord {
+  fn from(value: Modifier) -> Self {
+    Chord::new(value)
+  }
+}
+
+impl From<GamepadButton> for Chord {
+  fn from(value: GamepadButton) -> Self {
+    Chord::new(value)
+  }
+}
+
+/// A virtual axis in `[-1, 1]` driven by two sets of chords and, optionally,
+/// analog gamepad axes.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Axis {
+  pub negative: Vec<Chord>,
+  pub positive: Vec<Chord>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub gamepad: Vec<GamepadAxis>,
+}
+
+impl Axis {
+  pub fn new(negative: impl Into<Chord>, positive: impl Into<Chord>) -> Self {
+    Self {
+      negative: vec![negative.into()],
+      positive: vec![positive.into()],
+      gamepad: Vec::new(),
+    }
+  }
+
+  pub fn with(mut self, negative: impl Into<Chord>, positive: impl Into<Chord>) -> Self {
+    self.negative.push(negative.into());
+    self.positive.push(positive.into());
+    self
+  }
+
+  /// Also drives the axis from `axis` on any connected gamepad.
+  pub fn with_gamepad(mut self, axis: GamepadAxis) -> Self {
+    self.gamepad.push(axis);
+    self
+  }
+
+  pub fn value(&self, input: &Input) -> f32 {
+    let held = |chords: &[Chord]| chords.iter().any(|chord| chord.pressed(input)) as i8 as f32;
+    let analog: f32 = self.gamepad.iter().map(|axis| input.any_gamepad_axis(*axis)).sum();
+    (held(&self.positive) - held(&self.negative) + analog).clamp(-1.0, 1.0)
+  }
+}
+
+/// Two axes combined, e.g. WASD movement. The value is clamped to the unit
+/// circle so diagonals aren't faster.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Axis2 {
+  pub x: Axis,
+  pub y: Axis,
+}
+
+impl Axis2 {
+  pub fn new(x: Axis, y: Axis) -> Self {
+    Self { x, y }
+  }
+
+  pub fn value(&self, input: &Input) -> (f32, f32) {
+    let (x, y) = (self.x.value(input), self.y.value(input));
+    let length = (x * x + y * y).sqrt();
+    if length > 1.0 {
+      (x / length, y / length)
+    } else {
+      (x, y)
+    }
+  }
+}
+
+/// Named actions and axes, queried through [`Input::action`],
+/// [`Input::axis`] and [`Input::axis2`]. Serializable so bindings can live in
+/// a config file and be changed by players.
+///
+/// ```ignore
+/// let mut actions = ActionMap::default();
+/// actions.bind("jump", KeyCode::Space).bind("jump", GamepadButton::South);
+/// actions.bind("save", Chord::new(KeyCode::S).with(Modifier::Ctrl));
+/// actions.bind("inventory", LogicalKey::character("i"));
+/// actions.bind_axis2("move", Axis2::new(Axis::new(KeyCode::A, KeyCode::D), Axis::new(KeyCode::S, KeyCode::W)));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActionMap {
+  #[serde(default)]
+  actions: HashMap<String, Vec<Chord>>,
+  #[serde(default)]
+  axes: HashMap<String, Axis>,
+  #[serde(default)]
+  axes2: HashMap<String, Axis2>,
+}
+
+impl ActionMap {
+  /// Adds `chord` to the chords that trigger `action`.
+  pub fn bind(&mut self, action: impl Into<String>, chord: impl Into<Chord>) -> &mut Self {
+    self.actions.entry(action.into()).or_default().push(chord.into());
+    self
+  }
+
+  /// Removes every chord bound to `action`.
+  pub fn unbind(&mut self, action: &str) -> &mut Self {
+    self.actions.remove(action);
+    self
+  }
+
+  pub fn bind_axis(&mut self, axis: impl Into<String>, value: Axis) -> &mut Self {
+    self.axes.insert(axis.into(), value);
+    self
+  }
+
+  pub fn bind_axis2(&mut self, axis: impl Into<String>, value: Axis2) -> &mut Self {
+    self.axes2.insert(axis.into(), value);
+    self
+  }
+
+  pub fn bindings(&self, action: &str) -> &[Chord] {
+    self.actions.get(action).map(Vec::as_slice).unwrap_or_default()
+  }
+
+  pub fn axis(&self, axis: &str) -> Option<&Axis> {
+    self.axes.get(axis)
+  }
+
+  pub fn axis2(&self, axis: &str) -> Option<&Axis2> {
+    self.axes2.get(axis)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use quanta::Clock;
+  use serde::{Deserialize, Serialize};
+
+  use super::*;
+  use crate::core::{event::InputEvent, input::gamepad::GamepadId};
+
+  fn press(input: &mut Input, binding: impl Into<Binding>) {
+    let now = Clock::new().now();
+    match binding.into() {
+      Binding::Key(code) => input.key_buttons.press(code, now),
+      Binding::Modifier(modifier) => input.modifiers.press(modifier, now),
+      binding => unimplemented!("{binding:?}"),
+    }
+  }
+
+  fn tilt(input: &mut Input, axis: GamepadAxis, value: f32) {
+    let id = GamepadId(0);
+    input.update_gamepad(&InputEvent::GamepadConnected {
+      id,
+      name: "Test pad".into(),
+    });
+    input.update_gamepad(&InputEvent::GamepadAxis { id, axis, value });
+  }
+
+  #[test]
+  fn chords_need_their_modifiers() {
+    let mut input = Input::new();
+    input
+      .actions_mut()
+      .bind("save", Chord::new(KeyCode::S).with(Modifier::Ctrl));
+
+    press(&mut input, KeyCode::S);
+    assert!(!input.action("save"));
+    assert!(!input.action_just_pressed("save"));
+
+    press(&mut input, Modifier::Ctrl);
+    assert!(input.action("save"));
+    assert!(input.action_just_pressed("save"));
+  }
+
+  #[test]
+  fn axes_clamp_what_their_bindings_add_up_to() {
+    let mut input = Input::new();
+    input
+      .actions_mut()
+      .bind_axis(
+        "throttle",
+        Axis::new(KeyCode::S, KeyCode::W)
+          .with(KeyCode::Space, KeyCode::Space)
+          .with_gamepad(GamepadAxis::RightTrigger),
+      )
+      .bind_axis2(
+        "move",
+        Axis2::new(Axis::new(KeyCode::A, KeyCode::D), Axis::new(KeyCode::S, KeyCode::W)),
+      );
+
+    press(&mut input, KeyCode::W);
+    tilt(&mut input, GamepadAxis::RightTrigger, 1.0);
+    assert_eq!(input.axis("throttle"), 1.0);
+
+    // Chords on both ends cancel out, leaving the trigger
+    press(&mut input, KeyCode::Space);
+    assert_eq!(input.axis("throttle"), 1.0);
+    press(&mut input, KeyCode::S);
+    assert_eq!(input.axis("throttle"), 1.0);
+
+    press(&mut input, KeyCode::D);
+    let (x, y) = input.axis2("move");
+    assert_eq!((x, y), (1.0, 0.0), "opposite keys cancel on y");
+
+    press(&mut input, KeyCode::A);
+    input.key_buttons.release(KeyCode::S);
+    input.key_buttons.release(KeyCode::A);
+    let (x, y) = input.axis2("move");
+    assert!((x - y).abs() < 1e-6 && (x.hypot(y) - 1.0).abs() < 1e-6, "{x}, {y}");
+    assert_eq!(input.axis("unbound"), 0.0);
+  }
+
+  #[test]
+  fn action_maps_round_trip_through_serde() {
+    let mut actions = ActionMap::default();
+    actions
+      .bind("jump", KeyCode::Space)
+      .bind("jump", GamepadButton::South)
+      .bind("save", Chord::new(KeyCode::S).with(Modifier::Ctrl))
+      .bind("inventory", LogicalKey::character("i"))
+      .bind("fire", MouseCode::Left)
+      .bind_axis("zoom", Axis::new(KeyCode::Q, KeyCode::E).with_gamepad(GamepadAxis::RightStickY))
+      .bind_axis2(
+        "move",
+        Axis2::new(Axis::new(KeyCode::A, KeyCode::D), Axis::new(KeyCode::S, KeyCode::W)),
+      );
+
+    let value = actions.serialize(value::Serializer).unwrap();
+    assert_eq!(ActionMap::deserialize(value).unwrap(), actions);
+  }
+
+  /// Just enough of a self-describing format to round-trip serde types
+  /// without depending on a format crate.
+  mod value {
+    use std::fmt::{self, Display};
+
+    use serde::{
+      de::{
+        self,
+        value::{MapDeserializer, SeqDeserializer},
+        IntoDeserializer,
+        Visitor,
+      },
+      forward_to_deserialize_any,
+      ser,
+    };
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Value {
+      Unit,
+      Bool(bool),
+      Int(i64),
+      Uint(u64),
+      Float(f64),
+      Char(char),
+      Str(String),
+      Option(Option<Box<Value>>),
+      Seq(Vec<Value>),
+      Map(Vec<(Value, Value)>),
+      Variant(&'static str, Box<Value>),
+    }
+
+    #[derive(Debug)]
+    pub struct Error(String);
+
+    impl Display for Error {
+      fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+      }
+    }
+
+    impl std::error::Error for Error {}
+
+    impl ser::Error for Error {
+      fn custom<T: Display>(msg: T) -> Self {
+        Self(msg.to_string())
+      }
+    }
+
+    impl de::Error for Error {
+      fn custom<T: Display>(msg: T) -> Self {
+        Self(msg.to_string())
+      }
+    }
+
+    pub struct Serializer;
+
+    impl ser::Serializer for Serializer {
+      type Error = Error;
+      type Ok = Value;
+      type SerializeMap = Compound;
+      type SerializeSeq = Compound;
+      type SerializeStruct = Compound;
+      type SerializeStructVariant = Compound;
+      type SerializeTuple = Compound;
+      type SerializeTupleStruct = Compound;
+      type SerializeTupleVariant = Compound;
+
+      fn serialize_bool(self, v: bool) -> Result<Value, Error> {
+        Ok(Value::Bool(v))
+      }
+
+      fn serialize_i8(self, v: i8) -> Result<Value, Error> {
+        Ok(Value::Int(v.into()))
+      }
+
+      fn serialize_i16(self, v: i16) -> Result<Value, Error> {
+        Ok(Value::Int(v.into()))
+      }
+
+      fn serialize_i32(self, v: i32) -> Result<Value, Error> {
+        Ok(Value::Int(v.into()))
+      }
+
+      fn serialize_i64(self, v: i64) -> Result<Value, Error> {
+        Ok(Value::Int(v))
+      }
+
+      fn serialize_u8(self, v: u8) -> Result<Value, Error> {
+        Ok(Value::Uint(v.into()))
+      }
+
+      fn serialize_u16(self, v: u16) -> Result<Value, Error> {
+        Ok(Value::Uint(v.into()))
+      }
+
+      fn serialize_u32(self, v: u32) -> Result<Value, Error> {
+        Ok(Value::Uint(v.into()))
+      }
+
+      fn serialize_u64(self, v: u64) -> Result<Value, Error> {
+        Ok(Value::Uint(v))
+      }
+
+      fn serialize_f32(self, v: f32) -> Result<Value, Error> {
+        Ok(Value::Float(v.into()))
+      }
+
+      fn serialize_f64(self, v: f64) -> Result<Value, Error> {
+        Ok(Value::Float(v))
+      }
+
+      fn serialize_char(self, v: char) -> Result<Value, Error> {
+        Ok(Value::Char(v))
+      }
+
+      fn serialize_str(self, v: &str) -> Result<Value, Error> {
+        Ok(Value::Str(v.to_owned()))
+      }
+
+      fn serialize_bytes(self, v: &[u8]) -> Result<Value, Error> {
+        Ok(Value::Seq(v.iter().map(|byte| Value::Uint((*byte).into())).collect()))
+      }
+
+      fn serialize_none(self) -> Result<Value, Error> {
+        Ok(Value::Option(None))
+      }
+
+      fn serialize_some<T: ?Sized + ser::Serialize>(self, value: &T) -> Result<Value, Error> {
+        Ok(Value::Option(Some(Box::new(value.serialize(self)?))))
+      }
+
+      fn serialize_unit(self) -> Result<Value, Error> {
+        Ok(Value::Unit)
+      }
+
+      fn serialize_unit_struct(self, _name: &'static str) -> Result<Value, Error> {
+        Ok(Value::Unit)
+      }
+
+      fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<Value, Error> {
+        Ok(Value::Variant(variant, Box::new(Value::Unit)))
+      }
+
+      fn serialize_newtype_struct<T: ?Sized + ser::Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+      ) -> Result<Value, Error> {
+        value.serialize(self)
+      }
+
+      fn serialize_newtype_variant<T: ?Sized + ser::Serialize>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+      ) -> Result<Value, Error> {
+        Ok(Value::Variant(variant, Box::new(value.serialize(self)?)))
+      }
+
+      fn serialize_seq(self, _len: Option<usize>) -> Result<Compound, Error> {
+        Ok(Compound::default())
+      }
+
+      fn serialize_tuple(self, _len: usize) -> Result<Compound, Error> {
+        Ok(Compound::default())
+      }
+
+      fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Compound, Error> {
+        Ok(Compound::default())
+      }
+
+      fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+      ) -> Result<Compound, Error> {
+        Ok(Compound::variant(variant))
+      }
+
+      fn serialize_map(self, _len: Option<usize>) -> Result<Compound, Error> {
+        Ok(Compound::default())
+      }
+
+      fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Compound, Error> {
+        Ok(Compound::default())
+      }
+
+      fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+      ) -> Result<Compound, Error> {
+        Ok(Compound::variant(variant))
+      }
+    }
+
+    /// Collects the items of a sequence or the entries of a map, optionally
+    /// wrapped in an enum variant.
+    #[derive(Default)]
+    pub struct Compound {
+      variant: Option<&'static str>,
+      items: Vec<Value>,
+      entries: Vec<(Value, Value)>,
+      key: Option<Value>,
+    }
+
+    impl Compound {
+      fn variant(variant: &'static str) -> Self {
+        Self {
+          variant: Some(variant),
+          ..Default::default()
+        }
+      }
+
+      fn wrap(self, value: Value) -> Value {
+        match self.variant {
+          Some(variant) => Value::Variant(variant, Box::new(value)),
+          None => value,
+        }
+      }
+
+      fn push<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.items.push(value.serialize(Serializer)?);
+        Ok(())
+      }
+
+      fn entry<T: ?Sized + ser::Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+        self
+          .entries
+          .push((Value::Str(key.to_owned()), value.serialize(Serializer)?));
+        Ok(())
+      }
+
+      fn seq(mut self) -> Result<Value, Error> {
+        let items = Value::Seq(std::mem::take(&mut self.items));
+        Ok(self.wrap(items))
+      }
+
+      fn map(mut self) -> Result<Value, Error> {
+        let entries = Value::Map(std::mem::take(&mut self.entries));
+        Ok(self.wrap(entries))
+      }
+    }
+
+    impl ser::SerializeSeq for Compound {
+      type Error = Error;
+      type Ok = Value;
+
+      fn serialize_element<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+      }
+
+      fn end(self) -> Result<Value, Error> {
+        self.seq()
+      }
+    }
+
+    impl ser::SerializeTuple for Compound {
+      type Error = Error;
+      type Ok = Value;
+
+      fn serialize_element<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+      }
+
+      fn end(self) -> Result<Value, Error> {
+        self.seq()
+      }
+    }
+
+    impl ser::SerializeTupleStruct for Compound {
+      type Error = Error;
+      type Ok = Value;
+
+      fn serialize_field<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+      }
+
+      fn end(self) -> Result<Value, Error> {
+        self.seq()
+      }
+    }
+
+    impl ser::SerializeTupleVariant for Compound {
+      type Error = Error;
+      type Ok = Value;
+
+      fn serialize_field<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+      }
+
+      fn end(self) -> Result<Value, Error> {
+        self.seq()
+      }
+    }
+
+    impl ser::SerializeMap for Compound {
+      type Error = Error;
+      type Ok = Value;
+
+      fn serialize_key<T: ?Sized + ser::Serialize>(&mut self, key: &T) -> Result<(), Error> {
+        self.key = Some(key.serialize(Serializer)?);
+        Ok(())
+      }
+
+      fn serialize_value<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self.key.take().ok_or_else(|| Error("value without a key".into()))?;
+        self.entries.push((key, value.serialize(Serializer)?));
+        Ok(())
+      }
+
+      fn end(self) -> Result<Value, Error> {
+        self.map()
+      }
+    }
+
+    impl ser::SerializeStruct for Compound {
+      type Error = Error;
+      type Ok = Value;
+
+      fn serialize_field<T: ?Sized + ser::Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+        self.entry(key, value)
+      }
+
+      fn end(self) -> Result<Value, Error> {
+        self.map()
+      }
+    }
+
+    impl ser::SerializeStructVariant for Compound {
+      type Error = Error;
+      type Ok = Value;
+
+      fn serialize_field<T: ?Sized + ser::Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+        self.entry(key, value)
+      }
+
+      fn end(self) -> Result<Value, Error> {
+        self.map()
+      }
+    }
+
+    impl<'de> IntoDeserializer<'de, Error> for Value {
+      type Deserializer = Self;
+
+      fn into_deserializer(self) -> Self {
+        self
+      }
+    }
+
+    impl<'de> de::Deserializer<'de> for Value {
+      type Error = Error;
+
+      forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+      }
+
+      fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+          Value::Unit => visitor.visit_unit(),
+          Value::Bool(v) => visitor.visit_bool(v),
+          Value::Int(v) => visitor.visit_i64(v),
+          Value::Uint(v) => visitor.visit_u64(v),
+          Value::Float(v) => visitor.visit_f64(v),
+          Value::Char(v) => visitor.visit_char(v),
+          Value::Str(v) => visitor.visit_string(v),
+          Value::Option(None) => visitor.visit_none(),
+          Value::Option(Some(v)) => visitor.visit_some(*v),
+          Value::Seq(items) => visitor.visit_seq(SeqDeserializer::new(items.into_iter())),
+          Value::Map(entries) => visitor.visit_map(MapDeserializer::new(entries.into_iter())),
+          Value::Variant(..) => visitor.visit_enum(self),
+        }
+      }
+    }
+
+    impl<'de> de::EnumAccess<'de> for Value {
+      type Error = Error;
+      type Variant = Value;
+
+      fn variant_seed<V: de::DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Value), Error> {
+        let Value::Variant(variant, body) = self else {
+          return Err(Error(format!("expected an enum, found {self:?}")));
+        };
+        Ok((seed.deserialize(Value::Str(variant.to_owned()))?, *body))
+      }
+    }
+
+    impl<'de> de::VariantAccess<'de> for Value {
+      type Error = Error;
+
+      fn unit_variant(self) -> Result<(), Error> {
+        Ok(())
+      }
+
+      fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(self)
+      }
+
+      fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_any(self, visitor)
+      }
+
+      fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+      ) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_any(self, visitor)
+      }
+    }
+  }
+}
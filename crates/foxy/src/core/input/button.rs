@@ -0,0 +1,244 @@
+use std::{
+  collections::{HashMap, HashSet},
+  hash::Hash,
+  time::Duration,
+};
+
+use quanta::Instant;
+
+use super::{
+  gamepad::{GamepadButton, GamepadId},
+  key::KeyCode,
+  logical::LogicalKey,
+  modifier::Modifier,
+  mouse::MouseCode,
+  Input,
+};
+
+/// Anything [`Input`] tracks with press/release edges: [`KeyCode`],
+/// [`LogicalKey`], [`MouseCode`], [`Modifier`] and gamepad buttons, which are
+/// addressed per gamepad as `(GamepadId, GamepadButton)`.
+pub trait Button: Clone + Eq + Hash {
+  #[doc(hidden)]
+  fn tracker(input: &Input) -> &ButtonTracker<Self>;
+}
+
+impl Button for KeyCode {
+  fn tracker(input: &Input) -> &ButtonTracker<Self> {
+    &input.key_buttons
+  }
+}
+
+impl Button for LogicalKey {
+  fn tracker(input: &Input) -> &ButtonTracker<Self> {
+    &input.logical_keys
+  }
+}
+
+impl Button for MouseCode {
+  fn tracker(input: &Input) -> &ButtonTracker<Self> {
+    &input.mouse_buttons
+  }
+}
+
+impl Button for Modifier {
+  fn tracker(input: &Input) -> &ButtonTracker<Self> {
+    &input.modifiers
+  }
+}
+
+impl Button for (GamepadId, GamepadButton) {
+  fn tracker(input: &Input) -> &ButtonTracker<Self> {
+    &input.gamepad_buttons
+  }
+}
+
+#[derive(Debug)]
+struct Edges<T> {
+  pressed: HashSet<T>,
+  released: HashSet<T>,
+}
+
+impl<T> Default for Edges<T> {
+  fn default() -> Self {
+    Self {
+      pressed: HashSet::new(),
+      released: HashSet::new(),
+    }
+  }
+}
+
+impl<T> Edges<T> {
+  fn clear(&mut self) {
+    self.pressed.clear();
+    self.released.clear();
+  }
+}
+
+/// Held buttons plus the presses and releases seen since the last frame and
+/// since the last fixed tick. Both edge sets are kept so that a press landing
+/// on a frame without a fixed tick is still seen by the next `fixed_update`.
+#[derive(Debug)]
+pub struct ButtonTracker<T> {
+  held: HashMap<T, Instant>,
+  frame: Edges<T>,
+  tick: Edges<T>,
+}
+
+impl<T> Default for ButtonTracker<T> {
+  fn default() -> Self {
+    Self {
+      held: HashMap::new(),
+      frame: Edges::default(),
+      tick: Edges::default(),
+    }
+  }
+}
+
+impl<T: Clone + Eq + Hash> ButtonTracker<T> {
+  pub fn is_pressed(&self, button: &T) -> bool {
+    self.held.contains_key(button)
+  }
+
+  pub fn pressed_since(&self, button: &T) -> Option<Instant> {
+    self.held.get(button).copied()
+  }
+
+  pub(crate) fn just_pressed(&self, button: &T, fixed: bool) -> bool {
+    self.edges(fixed).pressed.contains(button)
+  }
+
+  pub(crate) fn just_released(&self, button: &T, fixed: bool) -> bool {
+    self.edges(fixed).released.contains(button)
+  }
+
+  pub(crate) fn just_released_any(&self, predicate: impl FnMut(&T) -> bool, fixed: bool) -> bool {
+    self.edges(fixed).released.iter().any(predicate)
+  }
+
+  pub(crate) fn press(&mut self, button: T, now: Instant) {
+    if self.held.contains_key(&button) {
+      return;
+    }
+
+    self.held.insert(button.clone(), now);
+    self.frame.pressed.insert(button.clone());
+    self.tick.pressed.insert(button);
+  }
+
+  pub(crate) fn release(&mut self, button: T) {
+    if self.held.remove(&button).is_none() {
+      return;
+    }
+
+    self.frame.released.insert(button.clone());
+    self.tick.released.insert(button);
+  }
+
+  pub(crate) fn set(&mut self, button: T, pressed: bool, now: Instant) {
+    if pressed {
+      self.press(button, now);
+    } else {
+      self.release(button);
+    }
+  }
+
+  pub(crate) fn held_for(&self, button: &T, now: Instant) -> Option<Duration> {
+    self.held.get(button).map(|since| now.saturating_duration_since(*since))
+  }
+
+  pub(crate) fn held(&self) -> impl Iterator<Item = &T> {
+    self.held.keys()
+  }
+
+  pub(crate) fn end_frame(&mut self) {
+    self.frame.clear();
+  }
+
+  pub(crate) fn end_tick(&mut self) {
+    self.tick.clear();
+  }
+
+  fn edges(&self, fixed: bool) -> &Edges<T> {
+    if fixed {
+      &self.tick
+    } else {
+      &self.frame
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use quanta::Clock;
+
+  use super::*;
+
+  const FRAME: bool = false;
+  const TICK: bool = true;
+
+  #[test]
+  fn presses_wait_for_the_next_tick() {
+    let clock = Clock::new();
+    let mut buttons = ButtonTracker::default();
+    buttons.press('a', clock.now());
+    // A frame that runs no fixed ticks
+    assert!(buttons.just_pressed(&'a', FRAME));
+    buttons.end_frame();
+
+    assert!(!buttons.just_pressed(&'a', FRAME));
+    assert!(buttons.just_pressed(&'a', TICK));
+    buttons.end_tick();
+    assert!(!buttons.just_pressed(&'a', TICK));
+    assert!(buttons.is_pressed(&'a'));
+  }
+
+  #[test]
+  fn tapping_within_a_frame_shows_both_edges() {
+    let clock = Clock::new();
+    let mut buttons = ButtonTracker::default();
+    buttons.press('a', clock.now());
+    buttons.release('a');
+
+    assert!(!buttons.is_pressed(&'a'));
+    for edges in [FRAME, TICK] {
+      assert!(buttons.just_pressed(&'a', edges));
+      assert!(buttons.just_released(&'a', edges));
+    }
+  }
+
+  #[test]
+  fn frames_and_ticks_clear_their_own_edges() {
+    let clock = Clock::new();
+    let mut buttons = ButtonTracker::default();
+    buttons.press('a', clock.now());
+    buttons.end_tick();
+    assert!(buttons.just_pressed(&'a', FRAME));
+    assert!(!buttons.just_pressed(&'a', TICK));
+
+    buttons.release('a');
+    buttons.end_frame();
+    assert!(!buttons.just_released(&'a', FRAME));
+    assert!(buttons.just_released(&'a', TICK));
+    assert!(buttons.just_released_any(|button| *button == 'a', TICK));
+  }
+
+  #[test]
+  fn held_for_counts_from_the_press() {
+    let (clock, mock) = Clock::mock();
+    let mut buttons = ButtonTracker::default();
+    mock.increment(Duration::from_millis(100));
+    let pressed_at = clock.now();
+    buttons.press('a', pressed_at);
+
+    mock.increment(Duration::from_millis(250));
+    // Pressing again while held keeps the original press time
+    buttons.press('a', clock.now());
+    mock.increment(Duration::from_millis(50));
+
+    assert_eq!(buttons.pressed_since(&'a'), Some(pressed_at));
+    assert_eq!(buttons.held_for(&'a', clock.now()), Some(Duration::from_millis(300)));
+    buttons.release('a');
+    assert_eq!(buttons.held_for(&'a', clock.now()), None);
+  }
+}
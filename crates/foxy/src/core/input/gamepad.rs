@@ -0,0 +1,200 @@
+#[cfg(feature = "gilrs")]
+mod gilrs;
+mod virtual_gamepad;
+
+use std::fmt::Debug;
+
+use serde::{Deserialize, Serialize};
+use strum::EnumIter;
+use tracing::*;
+
+#[cfg(feature = "gilrs")]
+pub use self::gilrs::GilrsBackend;
+pub use self::virtual_gamepad::VirtualGamepads;
+use crate::core::event::InputEvent;
+
+/// Identifies a connected gamepad. Ids are assigned by the backend and may be
+/// reused after a gamepad disconnects.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct GamepadId(pub usize);
+
+/// Gamepad buttons, named by position so that the same binding works on Xbox,
+/// PlayStation and Nintendo layouts.
+#[derive(EnumIter, Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum GamepadButton {
+  South,
+  East,
+  North,
+  West,
+  LeftBumper,
+  RightBumper,
+  /// Digital press of the left trigger. Its analog value is
+  /// [`GamepadAxis::LeftTrigger`].
+  LeftTrigger,
+  /// Digital press of the right trigger. Its analog value is
+  /// [`GamepadAxis::RightTrigger`].
+  RightTrigger,
+  Select,
+  Start,
+  Mode,
+  LeftThumb,
+  RightThumb,
+  DPadUp,
+  DPadDown,
+  DPadLeft,
+  DPadRight,
+}
+
+/// Analog gamepad inputs. Sticks are in `[-1, 1]` with positive `y` pointing
+/// up, triggers are in `[0, 1]`.
+#[derive(EnumIter, Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum GamepadAxis {
+  LeftStickX,
+  LeftStickY,
+  RightStickX,
+  RightStickY,
+  LeftTrigger,
+  RightTrigger,
+}
+
+impl GamepadAxis {
+  /// The stick this axis belongs to, or `None` for triggers.
+  pub fn stick(self) -> Option<Stick> {
+    match self {
+      GamepadAxis::LeftStickX | GamepadAxis::LeftStickY => Some(Stick::Left),
+      GamepadAxis::RightStickX | GamepadAxis::RightStickY => Some(Stick::Right),
+      GamepadAxis::LeftTrigger | GamepadAxis::RightTrigger => None,
+    }
+  }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Stick {
+  Left,
+  Right,
+}
+
+impl Stick {
+  pub fn axes(self) -> (GamepadAxis, GamepadAxis) {
+    match self {
+      Stick::Left => (GamepadAxis::LeftStickX, GamepadAxis::LeftStickY),
+      Stick::Right => (GamepadAxis::RightStickX, GamepadAxis::RightStickY),
+    }
+  }
+}
+
+/// Values below `inner` read as zero and values above `outer` read as fully
+/// pushed, with the range in between rescaled to `[0, 1]`. This hides stick
+/// drift and worn triggers that never quite reach the end of their travel.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeadZone {
+  pub inner: f32,
+  pub outer: f32,
+}
+
+impl DeadZone {
+  pub fn new(inner: f32, outer: f32) -> Self {
+    Self { inner, outer }
+  }
+
+  /// Rescales a magnitude in `[0, 1]`.
+  pub fn apply(&self, magnitude: f32) -> f32 {
+    if magnitude <= self.inner {
+      return 0.0;
+    }
+
+    let range = self.outer - self.inner;
+    if range <= f32::EPSILON {
+      return 1.0;
+    }
+
+    ((magnitude - self.inner) / range).min(1.0)
+  }
+
+  /// Applies the dead zone to a stick's length rather than to each axis, so
+  /// that diagonals aren't snapped to the axes.
+  pub fn apply_radial(&self, (x, y): (f32, f32)) -> (f32, f32) {
+    let length = (x * x + y * y).sqrt();
+    if length <= f32::EPSILON {
+      return (0.0, 0.0);
+    }
+
+    let scale = self.apply(length) / length;
+    (x * scale, y * scale)
+  }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GamepadSettings {
+  pub stick_dead_zone: DeadZone,
+  pub trigger_dead_zone: DeadZone,
+}
+
+impl Default for GamepadSettings {
+  fn default() -> Self {
+    Self {
+      stick_dead_zone: DeadZone::new(0.15, 0.95),
+      trigger_dead_zone: DeadZone::new(0.05, 1.0),
+    }
+  }
+}
+
+/// A source of gamepad events, polled once per frame on the game thread.
+///
+/// Backends report raw values through the gamepad variants of
+/// [`InputEvent`]: `GamepadConnected`, `GamepadDisconnected`, `GamepadButton`
+/// and `GamepadAxis`. Dead zones are applied by
+/// [`Input`](super::Input) when the values are queried.
+pub trait GamepadBackend: Debug {
+  fn poll(&mut self, events: &mut Vec<InputEvent>);
+}
+
+/// Which [`GamepadBackend`] Foxy should use.
+///
+/// Native gamepads need foxy's `gilrs` feature, which is off by default since
+/// it needs libudev on Linux. Without it, [`Native`](Gamepads::Native) logs a
+/// warning and no gamepads are reported.
+#[derive(Debug, Default)]
+pub enum Gamepads {
+  Disabled,
+  /// gilrs when the `gilrs` feature is enabled, otherwise no gamepads and a
+  /// warning.
+  #[default]
+  Native,
+  Custom(Box<dyn GamepadBackend + Send>),
+}
+
+impl Gamepads {
+  /// Creates the backend. Called on the thread that polls it, since native
+  /// backends aren't necessarily `Send`.
+  pub(crate) fn into_backend(self) -> Option<Box<dyn GamepadBackend>> {
+    match self {
+      Gamepads::Disabled => None,
+      Gamepads::Native => Self::native(),
+      Gamepads::Custom(backend) => Some(backend),
+    }
+  }
+
+  #[cfg(feature = "gilrs")]
+  fn native() -> Option<Box<dyn GamepadBackend>> {
+    match GilrsBackend::new() {
+      Ok(backend) => Some(Box::new(backend)),
+      Err(error) => {
+        warn!("gamepads unavailable: {error}");
+        None
+      }
+    }
+  }
+
+  #[cfg(not(feature = "gilrs"))]
+  fn native() -> Option<Box<dyn GamepadBackend>> {
+    warn!("foxy was built without the `gilrs` feature, gamepads are disabled");
+    None
+  }
+}
+
+impl<B: GamepadBackend + Send + 'static> From<B> for Gamepads {
+  fn from(value: B) -> Self {
+    Gamepads::Custom(Box::new(value))
+  }
+}
@@ -0,0 +1,102 @@
+use gilrs::{Axis, Button, EventType, Gilrs};
+
+use super::{GamepadAxis, GamepadBackend, GamepadButton, GamepadId};
+use crate::{
+  core::{event::InputEvent, input::state::ButtonState, FoxyResult},
+  foxy_error,
+};
+
+/// Native gamepads through gilrs.
+#[derive(Debug)]
+pub struct GilrsBackend {
+  gilrs: Gilrs,
+}
+
+impl GilrsBackend {
+  pub fn new() -> FoxyResult<Self> {
+    let gilrs = Gilrs::new().map_err(|error| foxy_error!("failed to initialize gilrs: {error}"))?;
+    Ok(Self { gilrs })
+  }
+}
+
+impl GamepadBackend for GilrsBackend {
+  fn poll(&mut self, events: &mut Vec<InputEvent>) {
+    while let Some(event) = self.gilrs.next_event() {
+      let id = GamepadId(event.id.into());
+      let event = match event.event {
+        EventType::Connected => InputEvent::GamepadConnected {
+          id,
+          name: self.gilrs.gamepad(event.id).name().to_owned(),
+        },
+        EventType::Disconnected => InputEvent::GamepadDisconnected { id },
+        EventType::ButtonPressed(button, _) => match map_button(button) {
+          Some(button) => InputEvent::GamepadButton {
+            id,
+            button,
+            state: ButtonState::Pressed,
+          },
+          None => continue,
+        },
+        EventType::ButtonReleased(button, _) => match map_button(button) {
+          Some(button) => InputEvent::GamepadButton {
+            id,
+            button,
+            state: ButtonState::Released,
+          },
+          None => continue,
+        },
+        // Analog triggers are reported as button values.
+        EventType::ButtonChanged(Button::LeftTrigger2, value, _) => InputEvent::GamepadAxis {
+          id,
+          axis: GamepadAxis::LeftTrigger,
+          value,
+        },
+        EventType::ButtonChanged(Button::RightTrigger2, value, _) => InputEvent::GamepadAxis {
+          id,
+          axis: GamepadAxis::RightTrigger,
+          value,
+        },
+        EventType::AxisChanged(axis, value, _) => match map_axis(axis) {
+          Some(axis) => InputEvent::GamepadAxis { id, axis, value },
+          None => continue,
+        },
+        _ => continue,
+      };
+
+      events.push(event);
+    }
+  }
+}
+
+fn map_button(button: Button) -> Option<GamepadButton> {
+  Some(match button {
+    Button::South => GamepadButton::South,
+    Button::East => GamepadButton::East,
+    Button::North => GamepadButton::North,
+    Button::West => GamepadButton::West,
+    Button::LeftTrigger => GamepadButton::LeftBumper,
+    Button::RightTrigger => GamepadButton::RightBumper,
+    Button::LeftTrigger2 => GamepadButton::LeftTrigger,
+    Button::RightTrigger2 => GamepadButton::RightTrigger,
+    Button::Select => GamepadButton::Select,
+    Button::Start => GamepadButton::Start,
+    Button::Mode => GamepadButton::Mode,
+    Button::LeftThumb => GamepadButton::LeftThumb,
+    Button::RightThumb => GamepadButton::RightThumb,
+    Button::DPadUp => GamepadButton::DPadUp,
+    Button::DPadDown => GamepadButton::DPadDown,
+    Button::DPadLeft => GamepadButton::DPadLeft,
+    Button::DPadRight => GamepadButton::DPadRight,
+    Button::C | Button::Z | Button::Unknown => return None,
+  })
+}
+
+fn map_axis(axis: Axis) -> Option<GamepadAxis> {
+  Some(match axis {
+    Axis::LeftStickX => GamepadAxis::LeftStickX,
+    Axis::LeftStickY => GamepadAxis::LeftStickY,
+    Axis::RightStickX => GamepadAxis::RightStickX,
+    Axis::RightStickY => GamepadAxis::RightStickY,
+    Axis::LeftZ | Axis::RightZ | Axis::DPadX | Axis::DPadY | Axis::Unknown => return None,
+  })
+}
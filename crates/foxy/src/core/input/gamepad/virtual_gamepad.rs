@@ -0,0 +1,107 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use tracing::*;
+
+use super::{GamepadAxis, GamepadBackend, GamepadButton, GamepadId, Stick};
+use crate::core::{event::InputEvent, input::state::ButtonState};
+
+#[derive(Debug, Default)]
+struct Shared {
+  next_id: usize,
+  connected: Vec<GamepadId>,
+  pending: Vec<InputEvent>,
+}
+
+/// In-process gamepads driven from code, for tests and headless runs. Clones
+/// share the same devices: hand one to
+/// [`FoxyCreateInfo::with_gamepads`](crate::core::builder::FoxyCreateInfo::with_gamepads)
+/// and keep another to press buttons. Changes are seen on the next frame.
+///
+/// ```ignore
+/// let pads = VirtualGamepads::default();
+/// let mut app = Framework::headless::<App>(FoxyCreateInfo::default().with_gamepads(pads.clone()));
+/// let id = pads.connect("Test pad");
+/// pads.press(id, GamepadButton::South);
+/// app.step();
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct VirtualGamepads {
+  shared: Arc<Mutex<Shared>>,
+}
+
+impl VirtualGamepads {
+  pub fn connect(&self, name: impl Into<String>) -> GamepadId {
+    let mut shared = self.lock();
+    let id = GamepadId(shared.next_id);
+    shared.next_id += 1;
+    shared.connected.push(id);
+    shared
+      .pending
+      .push(InputEvent::GamepadConnected { id, name: name.into() });
+    id
+  }
+
+  pub fn disconnect(&self, id: GamepadId) {
+    let mut shared = self.lock();
+    if let Some(index) = shared.connected.iter().position(|connected| *connected == id) {
+      shared.connected.remove(index);
+      shared.pending.push(InputEvent::GamepadDisconnected { id });
+    }
+  }
+
+  pub fn is_connected(&self, id: GamepadId) -> bool {
+    self.lock().connected.contains(&id)
+  }
+
+  pub fn press(&self, id: GamepadId, button: GamepadButton) {
+    self.send(InputEvent::GamepadButton {
+      id,
+      button,
+      state: ButtonState::Pressed,
+    });
+  }
+
+  pub fn release(&self, id: GamepadId, button: GamepadButton) {
+    self.send(InputEvent::GamepadButton {
+      id,
+      button,
+      state: ButtonState::Released,
+    });
+  }
+
+  /// Sets a raw axis value, before dead zones are applied.
+  pub fn set_axis(&self, id: GamepadId, axis: GamepadAxis, value: f32) {
+    self.send(InputEvent::GamepadAxis { id, axis, value });
+  }
+
+  pub fn set_stick(&self, id: GamepadId, stick: Stick, (x, y): (f32, f32)) {
+    let (x_axis, y_axis) = stick.axes();
+    self.set_axis(id, x_axis, x);
+    self.set_axis(id, y_axis, y);
+  }
+
+  fn send(&self, event: InputEvent) {
+    let mut shared = self.lock();
+    let id = match &event {
+      InputEvent::GamepadButton { id, .. } | InputEvent::GamepadAxis { id, .. } => *id,
+      _ => return,
+    };
+
+    if shared.connected.contains(&id) {
+      shared.pending.push(event);
+    } else {
+      warn!("virtual gamepad {id:?} is not connected");
+    }
+  }
+
+  fn lock(&self) -> MutexGuard<'_, Shared> {
+    // A panic while holding the lock can't leave `Shared` half-updated.
+    self.shared.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+  }
+}
+
+impl GamepadBackend for VirtualGamepads {
+  fn poll(&mut self, events: &mut Vec<InputEvent>) {
+    events.append(&mut self.lock().pending);
+  }
+}
@@ -0,0 +1,406 @@
+use std::{collections::HashMap, f64::consts::PI, time::Duration};
+
+use quanta::Instant;
+use winit::dpi::PhysicalPosition;
+
+use super::touch::{Touch, TouchPhase};
+
+/// High level gestures recognized from touches and touchpads. Positions are
+/// in physical pixels.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Gesture {
+  /// A single finger pressed and lifted quickly without moving.
+  Tap { position: PhysicalPosition<f64> },
+  /// A single finger held still. Sent once, while the finger is still down;
+  /// lifting it afterwards doesn't produce a tap.
+  LongPress { position: PhysicalPosition<f64> },
+  /// A single finger dragged and lifted quickly. `velocity` is in pixels per
+  /// second.
+  Swipe {
+    start: PhysicalPosition<f64>,
+    end: PhysicalPosition<f64>,
+    velocity: (f64, f64),
+  },
+  /// Two fingers moved apart or together, or a touchpad pinch. `scale` is
+  /// relative to the previous pinch event, so multiply them together for the
+  /// total. `center` is `None` for touchpads.
+  Pinch {
+    scale: f64,
+    center: Option<PhysicalPosition<f64>>,
+  },
+  /// Two fingers twisted, or a touchpad rotation. `radians` is relative to the
+  /// previous rotate event, counterclockwise positive. `center` is `None` for
+  /// touchpads.
+  Rotate {
+    radians: f64,
+    center: Option<PhysicalPosition<f64>>,
+  },
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GestureSettings {
+  /// Longest press that still counts as a tap.
+  pub tap_max_duration: Duration,
+  /// How far, in physical pixels, a finger may drift and still tap or long
+  /// press.
+  pub tap_max_distance: f64,
+  pub long_press_duration: Duration,
+  pub swipe_min_distance: f64,
+  pub swipe_max_duration: Duration,
+}
+
+impl Default for GestureSettings {
+  fn default() -> Self {
+    Self {
+      tap_max_duration: Duration::from_millis(250),
+      tap_max_distance: 20.0,
+      long_press_duration: Duration::from_millis(500),
+      swipe_min_distance: 80.0,
+      swipe_max_duration: Duration::from_millis(500),
+    }
+  }
+}
+
+#[derive(Debug)]
+struct Tracked {
+  start: PhysicalPosition<f64>,
+  position: PhysicalPosition<f64>,
+  started_at: Instant,
+  /// Drifted further than `tap_max_distance` at some point.
+  wandered: bool,
+}
+
+/// Distance and angle between the two fingers of a pinch or rotate, as of the
+/// previous move.
+#[derive(Debug, Copy, Clone)]
+struct Span {
+  distance: f64,
+  angle: f64,
+}
+
+/// Turns raw [`Touch`]es into [`Gesture`]s. Single finger gestures are only
+/// recognized when no other finger touched the screen in the meantime, so a
+/// pinch doesn't end in a stray tap.
+#[derive(Debug, Default)]
+pub struct GestureRecognizer {
+  settings: GestureSettings,
+  touches: HashMap<u64, Tracked>,
+  span: Option<Span>,
+  /// Most fingers down at once since the screen was last clear.
+  max_touches: usize,
+  long_pressed: bool,
+}
+
+impl GestureRecognizer {
+  pub fn settings(&self) -> &GestureSettings {
+    &self.settings
+  }
+
+  pub fn settings_mut(&mut self) -> &mut GestureSettings {
+    &mut self.settings
+  }
+
+  pub(crate) fn touch(&mut self, touch: &Touch, now: Instant, gestures: &mut Vec<Gesture>) {
+    match touch.phase {
+      TouchPhase::Started => {
+        self.touches.insert(touch.id, Tracked {
+          start: touch.position,
+          position: touch.position,
+          started_at: now,
+          wandered: false,
+        });
+        self.max_touches = self.max_touches.max(self.touches.len());
+        self.span = self.current_span();
+      }
+      TouchPhase::Moved => {
+        let Some(tracked) = self.touches.get_mut(&touch.id) else {
+          return;
+        };
+        tracked.position = touch.position;
+        if distance(tracked.start, tracked.position) > self.settings.tap_max_distance {
+          tracked.wandered = true;
+        }
+
+        self.two_finger_gestures(gestures);
+      }
+      TouchPhase::Ended => {
+        let Some(tracked) = self.touches.remove(&touch.id) else {
+          return;
+        };
+
+        if self.max_touches == 1 && !self.long_pressed {
+          gestures.extend(self.single_finger_gesture(&tracked, touch.position, now));
+        }
+        self.finger_lifted();
+      }
+      TouchPhase::Cancelled => {
+        self.touches.remove(&touch.id);
+        self.finger_lifted();
+      }
+    }
+  }
+
+  /// Looks for long presses, which are recognized by time rather than by a
+  /// touch event. Called once per frame.
+  pub(crate) fn poll(&mut self, now: Instant, gestures: &mut Vec<Gesture>) {
+    if self.max_touches != 1 || self.long_pressed {
+      return;
+    }
+
+    let Some(tracked) = self.touches.values().next() else {
+      return;
+    };
+
+    if !tracked.wandered && now.saturating_duration_since(tracked.started_at) >= self.settings.long_press_duration {
+      self.long_pressed = true;
+      gestures.push(Gesture::LongPress {
+        position: tracked.position,
+      });
+    }
+  }
+
+  pub(crate) fn touchpad_magnify(&mut self, delta: f64, gestures: &mut Vec<Gesture>) {
+    gestures.push(Gesture::Pinch {
+      scale: 1.0 + delta,
+      center: None,
+    });
+  }
+
+  /// `degrees` as reported by winit.
+  pub(crate) fn touchpad_rotate(&mut self, degrees: f32, gestures: &mut Vec<Gesture>) {
+    gestures.push(Gesture::Rotate {
+      radians: f64::from(degrees).to_radians(),
+      center: None,
+    });
+  }
+
+  fn single_finger_gesture(&self, tracked: &Tracked, end: PhysicalPosition<f64>, now: Instant) -> Option<Gesture> {
+    let held = now.saturating_duration_since(tracked.started_at);
+    let travelled = distance(tracked.start, end);
+
+    if !tracked.wandered && held <= self.settings.tap_max_duration {
+      return Some(Gesture::Tap { position: end });
+    }
+
+    if travelled >= self.settings.swipe_min_distance && held <= self.settings.swipe_max_duration {
+      let seconds = held.as_secs_f64().max(f64::EPSILON);
+      return Some(Gesture::Swipe {
+        start: tracked.start,
+        end,
+        velocity: ((end.x - tracked.start.x) / seconds, (end.y - tracked.start.y) / seconds),
+      });
+    }
+
+    None
+  }
+
+  fn two_finger_gestures(&mut self, gestures: &mut Vec<Gesture>) {
+    let (Some(previous), Some(current)) = (self.span, self.current_span()) else {
+      return;
+    };
+    self.span = Some(current);
+    let center = self.center();
+
+    if previous.distance > f64::EPSILON && current.distance != previous.distance {
+      gestures.push(Gesture::Pinch {
+        scale: current.distance / previous.distance,
+        center,
+      });
+    }
+
+    let radians = wrap_angle(current.angle - previous.angle);
+    if radians != 0.0 {
+      gestures.push(Gesture::Rotate { radians, center });
+    }
+  }
+
+  fn finger_lifted(&mut self) {
+    self.span = self.current_span();
+    if self.touches.is_empty() {
+      self.max_touches = 0;
+      self.long_pressed = false;
+    }
+  }
+
+  fn current_span(&self) -> Option<Span> {
+    let [a, b] = self.two_fingers()?;
+    // Screen y points down, so flip it to make counterclockwise positive.
+    Some(Span {
+      distance: distance(a, b),
+      angle: (a.y - b.y).atan2(b.x - a.x),
+    })
+  }
+
+  fn center(&self) -> Option<PhysicalPosition<f64>> {
+    let [a, b] = self.two_fingers()?;
+    Some(PhysicalPosition::new((a.x + b.x) / 2.0, (a.y + b.y) / 2.0))
+  }
+
+  /// The positions of exactly two fingers, in a stable order.
+  fn two_fingers(&self) -> Option<[PhysicalPosition<f64>; 2]> {
+    if self.touches.len() != 2 {
+      return None;
+    }
+
+    let mut fingers: Vec<_> = self.touches.iter().collect();
+    fingers.sort_by_key(|(id, _)| **id);
+    Some([fingers[0].1.position, fingers[1].1.position])
+  }
+}
+
+fn distance(a: PhysicalPosition<f64>, b: PhysicalPosition<f64>) -> f64 {
+  (b.x - a.x).hypot(b.y - a.y)
+}
+
+fn wrap_angle(radians: f64) -> f64 {
+  (radians + PI).rem_euclid(2.0 * PI) - PI
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use quanta::{Clock, Mock};
+
+  use super::*;
+
+  /// A recognizer on a clock that only moves when told to.
+  struct Fingers {
+    recognizer: GestureRecognizer,
+    clock: Clock,
+    mock: Arc<Mock>,
+  }
+
+  impl Fingers {
+    fn new() -> Self {
+      let (clock, mock) = Clock::mock();
+      Self {
+        recognizer: GestureRecognizer::default(),
+        clock,
+        mock,
+      }
+    }
+
+    fn wait(&self, millis: u64) {
+      self.mock.increment(Duration::from_millis(millis));
+    }
+
+    fn touch(&mut self, id: u64, phase: TouchPhase, (x, y): (f64, f64)) -> Vec<Gesture> {
+      let touch = Touch {
+        id,
+        phase,
+        position: PhysicalPosition::new(x, y),
+        force: None,
+      };
+      let mut gestures = Vec::new();
+      self.recognizer.touch(&touch, self.clock.now(), &mut gestures);
+      gestures
+    }
+
+    fn poll(&mut self) -> Vec<Gesture> {
+      let mut gestures = Vec::new();
+      self.recognizer.poll(self.clock.now(), &mut gestures);
+      gestures
+    }
+  }
+
+  #[test]
+  fn quick_press_taps() {
+    let mut fingers = Fingers::new();
+    fingers.touch(0, TouchPhase::Started, (10.0, 10.0));
+    fingers.wait(100);
+    assert!(fingers.poll().is_empty());
+    assert_eq!(fingers.touch(0, TouchPhase::Ended, (12.0, 10.0)), [Gesture::Tap {
+      position: PhysicalPosition::new(12.0, 10.0)
+    }]);
+  }
+
+  #[test]
+  fn long_press_fires_once_and_swallows_the_tap() {
+    let mut fingers = Fingers::new();
+    fingers.touch(0, TouchPhase::Started, (10.0, 10.0));
+    fingers.wait(499);
+    assert!(fingers.poll().is_empty());
+
+    fingers.wait(1);
+    assert_eq!(fingers.poll(), [Gesture::LongPress {
+      position: PhysicalPosition::new(10.0, 10.0)
+    }]);
+    assert!(fingers.poll().is_empty());
+    assert!(fingers.touch(0, TouchPhase::Ended, (10.0, 10.0)).is_empty());
+
+    // The next press starts over
+    fingers.touch(1, TouchPhase::Started, (10.0, 10.0));
+    fingers.wait(50);
+    assert_eq!(fingers.touch(1, TouchPhase::Ended, (10.0, 10.0)).len(), 1);
+  }
+
+  #[test]
+  fn swipes_need_distance_and_speed() {
+    let mut fingers = Fingers::new();
+    fingers.touch(0, TouchPhase::Started, (0.0, 0.0));
+    fingers.wait(200);
+    fingers.touch(0, TouchPhase::Moved, (100.0, 0.0));
+    assert_eq!(fingers.touch(0, TouchPhase::Ended, (100.0, 0.0)), [Gesture::Swipe {
+      start: PhysicalPosition::new(0.0, 0.0),
+      end: PhysicalPosition::new(100.0, 0.0),
+      velocity: (500.0, 0.0),
+    }]);
+
+    // Too short to be a swipe, too far to be a tap
+    fingers.touch(1, TouchPhase::Started, (0.0, 0.0));
+    fingers.wait(100);
+    fingers.touch(1, TouchPhase::Moved, (50.0, 0.0));
+    assert!(fingers.touch(1, TouchPhase::Ended, (50.0, 0.0)).is_empty());
+
+    // Far enough, but too slow
+    fingers.touch(2, TouchPhase::Started, (0.0, 0.0));
+    fingers.wait(600);
+    fingers.touch(2, TouchPhase::Moved, (0.0, 200.0));
+    assert!(fingers.touch(2, TouchPhase::Ended, (0.0, 200.0)).is_empty());
+  }
+
+  #[test]
+  fn spreading_two_fingers_pinches() {
+    let mut fingers = Fingers::new();
+    fingers.touch(0, TouchPhase::Started, (0.0, 0.0));
+    fingers.touch(1, TouchPhase::Started, (100.0, 0.0));
+    assert_eq!(fingers.touch(1, TouchPhase::Moved, (200.0, 0.0)), [Gesture::Pinch {
+      scale: 2.0,
+      center: Some(PhysicalPosition::new(100.0, 0.0)),
+    }]);
+    assert_eq!(fingers.touch(1, TouchPhase::Moved, (100.0, 0.0)), [Gesture::Pinch {
+      scale: 0.5,
+      center: Some(PhysicalPosition::new(50.0, 0.0)),
+    }]);
+  }
+
+  #[test]
+  fn rotation_wraps_across_half_turns() {
+    let mut fingers = Fingers::new();
+    // The angle between the fingers starts just above -π
+    fingers.touch(0, TouchPhase::Started, (0.0, 0.0));
+    fingers.touch(1, TouchPhase::Started, (-100.0, 1.0));
+
+    // and moves to just below π, a small clockwise turn rather than a full one
+    let gestures = fingers.touch(1, TouchPhase::Moved, (-100.0, -1.0));
+    let [Gesture::Rotate { radians, center }] = gestures[..] else {
+      panic!("expected a single rotation, got {gestures:?}");
+    };
+    assert!((radians + 2.0 * 0.01f64.atan()).abs() < 1e-9, "{radians}");
+    assert_eq!(center, Some(PhysicalPosition::new(-50.0, -0.5)));
+  }
+
+  #[test]
+  fn two_finger_gestures_leave_no_stray_taps() {
+    let mut fingers = Fingers::new();
+    fingers.touch(0, TouchPhase::Started, (0.0, 0.0));
+    fingers.touch(1, TouchPhase::Started, (100.0, 0.0));
+    fingers.wait(50);
+    assert!(fingers.touch(1, TouchPhase::Ended, (100.0, 0.0)).is_empty());
+
+    fingers.wait(600);
+    assert!(fingers.poll().is_empty());
+    assert!(fingers.touch(0, TouchPhase::Ended, (0.0, 0.0)).is_empty());
+  }
+}
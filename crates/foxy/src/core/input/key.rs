@@ -1,8 +1,9 @@
+use serde::{Deserialize, Serialize};
 use strum::EnumIter;
 use winit::keyboard::{self, PhysicalKey};
 
 // Stolen from winit as a base to start from
-#[derive(EnumIter, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(EnumIter, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum KeyCode {
   Unknown,
   /// <kbd>`</kbd> on a US keyboard. This is also called a backtick or grave.
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+use winit::platform::modifier_supplement::KeyEventExtModifierSupplement;
+use winit::{
+  event::KeyEvent,
+  keyboard::{Key, NamedKey},
+};
+
+/// A key as the user's keyboard layout labels it, as opposed to
+/// [`KeyCode`](super::key::KeyCode), which is a physical position. The
+/// <kbd>Z</kbd> key on a US keyboard is `KeyCode::Z` everywhere, but
+/// `LogicalKey::Character("w")` on AZERTY.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LogicalKey {
+  Named(NamedKey),
+  /// What the key types without modifiers, lowercased so that bindings don't
+  /// depend on <kbd>Shift</kbd> or <kbd>CapsLock</kbd>.
+  Character(String),
+  Dead(Option<char>),
+  Unidentified,
+}
+
+impl LogicalKey {
+  pub fn character(text: impl AsRef<str>) -> Self {
+    LogicalKey::Character(text.as_ref().to_lowercase())
+  }
+
+  /// Human readable name, suitable for showing bindings to players.
+  pub fn label(&self) -> String {
+    match self {
+      LogicalKey::Named(named) => format!("{named:?}"),
+      LogicalKey::Character(text) => text.to_uppercase(),
+      LogicalKey::Dead(Some(c)) => c.to_string(),
+      LogicalKey::Dead(None) | LogicalKey::Unidentified => "?".to_owned(),
+    }
+  }
+
+  pub(crate) fn from_key_event(event: &KeyEvent) -> Self {
+    #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+    return event.key_without_modifiers().into();
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    return event.logical_key.clone().into();
+  }
+}
+
+impl From<Key> for LogicalKey {
+  fn from(value: Key) -> Self {
+    match value {
+      Key::Named(named) => LogicalKey::Named(named),
+      Key::Character(text) => LogicalKey::character(text),
+      Key::Dead(c) => LogicalKey::Dead(c),
+      Key::Unidentified(_) => LogicalKey::Unidentified,
+    }
+  }
+}
+
+impl From<NamedKey> for LogicalKey {
+  fn from(value: NamedKey) -> Self {
+    LogicalKey::Named(value)
+  }
+}
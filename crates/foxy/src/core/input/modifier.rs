@@ -2,10 +2,11 @@
 //
 // }
 
+use serde::{Deserialize, Serialize};
 use strum::EnumIter;
 use winit::keyboard::ModifiersState;
 
-#[derive(EnumIter, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(EnumIter, Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub enum Modifier {
   Shift,
   Ctrl,
@@ -1,7 +1,8 @@
+use serde::{Deserialize, Serialize};
 use strum::EnumIter;
-use winit::event::MouseButton;
+use winit::event::{MouseButton, MouseScrollDelta};
 
-#[derive(EnumIter, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(EnumIter, Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[repr(u16)]
 pub enum MouseCode {
   Left = 1,
@@ -24,3 +25,20 @@ impl From<MouseButton> for MouseCode {
     }
   }
 }
+
+/// Scroll amount from a mouse wheel (in lines) or a touchpad (in pixels).
+/// Positive `y` scrolls up, positive `x` scrolls right.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ScrollDelta {
+  Lines { x: f32, y: f32 },
+  Pixels { x: f64, y: f64 },
+}
+
+impl From<MouseScrollDelta> for ScrollDelta {
+  fn from(value: MouseScrollDelta) -> Self {
+    match value {
+      MouseScrollDelta::LineDelta(x, y) => ScrollDelta::Lines { x, y },
+      MouseScrollDelta::PixelDelta(delta) => ScrollDelta::Pixels { x: delta.x, y: delta.y },
+    }
+  }
+}
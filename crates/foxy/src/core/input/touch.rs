@@ -0,0 +1,51 @@
+use winit::dpi::{LogicalPosition, PhysicalPosition};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum TouchPhase {
+  Started,
+  Moved,
+  Ended,
+  /// The system took the touch away, e.g. for an OS gesture. Treat it as if
+  /// the touch never happened rather than as a release.
+  Cancelled,
+}
+
+impl From<winit::event::TouchPhase> for TouchPhase {
+  fn from(value: winit::event::TouchPhase) -> Self {
+    match value {
+      winit::event::TouchPhase::Started => TouchPhase::Started,
+      winit::event::TouchPhase::Moved => TouchPhase::Moved,
+      winit::event::TouchPhase::Ended => TouchPhase::Ended,
+      winit::event::TouchPhase::Cancelled => TouchPhase::Cancelled,
+    }
+  }
+}
+
+/// A finger on a touch screen.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Touch {
+  /// Unique while the finger is down; may be reused afterwards.
+  pub id: u64,
+  pub phase: TouchPhase,
+  /// Physical pixels, relative to the top-left of the window.
+  pub position: PhysicalPosition<f64>,
+  /// Pressure in `[0, 1]`, where the platform reports it.
+  pub force: Option<f64>,
+}
+
+impl Touch {
+  pub fn logical_position(&self, scale_factor: f64) -> LogicalPosition<f64> {
+    self.position.to_logical(scale_factor)
+  }
+}
+
+impl From<winit::event::Touch> for Touch {
+  fn from(value: winit::event::Touch) -> Self {
+    Self {
+      id: value.id,
+      phase: value.phase.into(),
+      position: value.location,
+      force: value.force.map(|force| force.normalized()),
+    }
+  }
+}
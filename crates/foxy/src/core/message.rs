@@ -1,12 +1,25 @@
-use egui::RawInput;
 use winit::event::WindowEvent;
 
+use super::{event::FoxyEvent, window_command::WindowCommand};
+
 #[derive(Debug)]
-pub enum RenderLoopMessage {
+pub enum RenderLoopMessage<T = ()> {
   Start,
   MustExit,
   ExitRequested,
   Winit(WindowEvent),
+  MouseMotion((f64, f64)),
+  UserEvent(T),
+  /// An event produced on the main thread, e.g. the result of a
+  /// [`WindowCommand`].
+  Event(FoxyEvent),
+  /// Wakes a sleeping [`Polling::Reactive`](super::builder::Polling) game
+  /// thread, e.g. for a redraw requested from another thread.
+  Wake,
+  /// The window was hidden, see
+  /// [`LifecyclePolicy`](super::builder::LifecyclePolicy).
+  Hidden,
+  Shown,
   None,
 }
 
@@ -15,3 +28,18 @@ pub enum GameLoopMessage {
   Exit,
   DontExit,
 }
+
+/// What travels through the winit event loop's user events.
+#[derive(Debug)]
+pub(crate) enum LoopEvent<T> {
+  User(T),
+  Command(WindowCommand),
+  /// Forwarded to the game thread as [`RenderLoopMessage::Wake`].
+  Wake,
+  /// The game thread queued a frame while running
+  /// [`Polling::Reactive`](super::builder::Polling).
+  FrameReady,
+  /// The game thread finished, whether it stopped normally, failed or
+  /// panicked.
+  GameExited,
+}
@@ -0,0 +1,60 @@
+use egui::Context;
+
+use super::{event::FoxyEvent, foxy_state::Foxy, schedule::Schedule, FoxyResult};
+
+/// A reusable feature, e.g. a debug overlay or a camera controller, added to
+/// an app with
+/// [`FoxyCreateInfo::with_plugin`](super::builder::FoxyCreateInfo::with_plugin).
+///
+/// Plugins run on the game thread alongside the app's
+/// [`Runnable`](super::runnable::Runnable), in the order they were added. In
+/// each [`Phase`](super::schedule::Phase) their hooks run after the app's and
+/// before the phase's systems. Like the app's hooks, an error stops the game
+/// thread.
+#[allow(unused)]
+pub trait Plugin: Send + 'static {
+  fn name(&self) -> &str {
+    std::any::type_name::<Self>()
+  }
+
+  /// Called once before the app is created. Add resources to `foxy` and
+  /// systems to `schedule` here, e.g. to handle events in a particular phase.
+  fn build(&mut self, foxy: &Foxy, schedule: &mut Schedule) -> FoxyResult<()> {
+    Ok(())
+  }
+
+  /// Called after [`Runnable::start`](super::runnable::Runnable::start),
+  /// including for the new app after a restart.
+  fn start(&mut self, foxy: &Foxy) -> FoxyResult<()> {
+    Ok(())
+  }
+
+  /// Called once per frame in
+  /// [`Phase::Update`](super::schedule::Phase::Update).
+  fn update(&mut self, foxy: &Foxy, events: &[FoxyEvent]) -> FoxyResult<()> {
+    Ok(())
+  }
+
+  /// Called once per frame in [`Phase::Gui`](super::schedule::Phase::Gui), to
+  /// draw panels next to the app's.
+  fn gui(&mut self, foxy: &Foxy, egui: &Context) -> FoxyResult<()> {
+    Ok(())
+  }
+
+  /// Called in reverse order of adding, right before the app is deleted:
+  ///
+  /// - when the game exits after [`Runnable::stop`] returned [`Flow::Exit`],
+  ///   or when the window is forced shut, where [`Runnable::stop`] still runs
+  ///   but its answer is ignored,
+  /// - when the app called
+  ///   [`State::request_exit`](super::foxy_state::State::request_exit), which
+  ///   doesn't consult [`Runnable::stop`],
+  /// - before every restart, whether from [`Flow::Restart`] or
+  ///   [`State::request_restart`](super::foxy_state::State::request_restart).
+  ///   [`start`](Plugin::start) is called again once the new app has started.
+  ///
+  /// [`Runnable::stop`]: super::runnable::Runnable::stop
+  /// [`Flow::Exit`]: super::runnable::Flow::Exit
+  /// [`Flow::Restart`]: super::runnable::Flow::Restart
+  fn stop(&mut self, foxy: &Foxy) {}
+}
@@ -0,0 +1,133 @@
+use std::{
+  any::{Any, TypeId},
+  collections::{hash_map::Entry, HashMap},
+  sync::Arc,
+};
+
+use parking_lot::{ArcRwLockReadGuard, ArcRwLockWriteGuard, RawRwLock, RwLock};
+
+/// Shared access to a resource. Other readers may hold it at the same time.
+pub type Res<T> = ArcRwLockReadGuard<RawRwLock, T>;
+/// Exclusive access to a resource.
+pub type ResMut<T> = ArcRwLockWriteGuard<RawRwLock, T>;
+
+/// Values shared across the app, one per type, e.g. settings, asset
+/// registries or score tables. Reached through
+/// [`Foxy::resource`](super::foxy_state::Foxy::resource) and friends.
+///
+/// Each resource has its own lock, so holding one doesn't block access to
+/// the others or to [`State`](super::foxy_state::State). Guards can be kept
+/// across calls into `foxy`, but holding two resources at once can deadlock
+/// like any pair of locks.
+#[derive(Default)]
+pub struct Resources {
+  // Each value is an `Arc<RwLock<T>>` for the `T` of its key
+  entries: RwLock<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>,
+}
+
+impl std::fmt::Debug for Resources {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("Resources")
+      .field("len", &self.entries.read().len())
+      .finish_non_exhaustive()
+  }
+}
+
+impl Resources {
+  /// Adds `value`, or replaces the current `T` and returns it. Waits for
+  /// anyone holding the current `T` to let go first.
+  pub fn insert<T: Send + Sync + 'static>(&self, value: T) -> Option<T> {
+    // The map is unlocked before waiting on the current value, so its holder
+    // can still reach other resources meanwhile
+    let current = match self.entries.write().entry(TypeId::of::<T>()) {
+      Entry::Occupied(entry) => entry.get().clone(),
+      Entry::Vacant(entry) => {
+        entry.insert(Arc::new(RwLock::new(value)));
+        return None;
+      }
+    };
+
+    downcast::<T>(current).map(|current| std::mem::replace(&mut *current.write(), value))
+  }
+
+  pub fn get<T: Send + Sync + 'static>(&self) -> Option<Res<T>> {
+    self.entry::<T>().map(|entry| entry.read_arc())
+  }
+
+  pub fn get_mut<T: Send + Sync + 'static>(&self) -> Option<ResMut<T>> {
+    self.entry::<T>().map(|entry| entry.write_arc())
+  }
+
+  pub fn contains<T: Send + Sync + 'static>(&self) -> bool {
+    self.entries.read().contains_key(&TypeId::of::<T>())
+  }
+
+  /// Takes the current `T` out, if there is one. Waits for anyone holding it
+  /// to let go first.
+  pub fn remove<T: Send + Sync + 'static>(&self) -> Option<T> {
+    let mut entry = self
+      .entries
+      .write()
+      .remove(&TypeId::of::<T>())
+      .and_then(downcast::<T>)?;
+
+    // Guards own a clone of the `Arc`, which they release just after the lock
+    loop {
+      match Arc::try_unwrap(entry) {
+        Ok(lock) => return Some(lock.into_inner()),
+        Err(shared) => {
+          drop(shared.write());
+          std::thread::yield_now();
+          entry = shared;
+        }
+      }
+    }
+  }
+
+  fn entry<T: Send + Sync + 'static>(&self) -> Option<Arc<RwLock<T>>> {
+    // Cloned so the map isn't locked for as long as the resource is
+    let entry = self.entries.read().get(&TypeId::of::<T>()).cloned();
+    entry.and_then(downcast::<T>)
+  }
+}
+
+fn downcast<T: Send + Sync + 'static>(entry: Arc<dyn Any + Send + Sync>) -> Option<Arc<RwLock<T>>> {
+  entry.downcast::<RwLock<T>>().ok()
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{thread, time::Duration};
+
+  use super::*;
+
+  #[derive(Debug, PartialEq)]
+  struct Score(u32);
+
+  #[test]
+  fn insert_and_remove_hand_back_values() {
+    let resources = Resources::default();
+    assert_eq!(resources.insert(Score(1)), None);
+    assert_eq!(resources.insert(Score(2)), Some(Score(1)));
+    assert_eq!(resources.get::<Score>().map(|score| score.0), Some(2));
+
+    assert_eq!(resources.remove::<Score>(), Some(Score(2)));
+    assert_eq!(resources.remove::<Score>(), None);
+    assert!(!resources.contains::<Score>());
+  }
+
+  #[test]
+  fn remove_waits_for_guards() {
+    let resources = Resources::default();
+    resources.insert(Score(0));
+    let mut score = resources.get_mut::<Score>().unwrap();
+
+    thread::scope(|scope| {
+      let removed = scope.spawn(|| resources.remove::<Score>());
+      thread::sleep(Duration::from_millis(20));
+      score.0 = 7;
+      drop(score);
+      assert_eq!(removed.join().unwrap(), Some(Score(7)));
+    });
+  }
+}
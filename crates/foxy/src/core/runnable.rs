@@ -2,35 +2,127 @@ use egui::Context;
 
 use super::{
   builder::FoxyCreateInfo,
-  foxy_state::Foxy,
   event::{FoxyEvent, InputEvent, WindowEvent},
   foxy_loop::Framework,
+  foxy_state::Foxy,
+  scene::Scene,
   FoxyResult,
 };
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+/// What to do next, returned from [`Runnable::stop`] and from the hooks of a
+/// [`Scene`]. The scene transitions only mean something to a
+/// [`SceneStack`](super::scene::SceneStack), and elsewhere count as
+/// [`Continue`](Flow::Continue).
 pub enum Flow {
   Exit,
   Continue,
+  /// Replaces the app with a fresh one without closing the window. See
+  /// [`State::request_restart`](super::foxy_state::State::request_restart).
+  Restart,
+  /// Covers the top scene with a new one.
+  Push(Box<dyn Scene>),
+  /// Removes the top scene, returning to the one below.
+  Pop,
+  /// Swaps the top scene for a new one.
+  Replace(Box<dyn Scene>),
 }
 
+impl Flow {
+  pub fn push(scene: impl Scene) -> Self {
+    Flow::Push(Box::new(scene))
+  }
+
+  pub fn replace(scene: impl Scene) -> Self {
+    Flow::Replace(Box::new(scene))
+  }
+
+  pub fn is_exit(&self) -> bool {
+    matches!(self, Flow::Exit)
+  }
+}
+
+impl std::fmt::Debug for Flow {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Flow::Exit => write!(f, "Exit"),
+      Flow::Continue => write!(f, "Continue"),
+      Flow::Restart => write!(f, "Restart"),
+      Flow::Push(scene) => f.debug_tuple("Push").field(&scene.name()).finish(),
+      Flow::Pop => write!(f, "Pop"),
+      Flow::Replace(scene) => f.debug_tuple("Replace").field(&scene.name()).finish(),
+    }
+  }
+}
+
+/// The app driven by a [`Framework`]. Hooks run on the game thread, in the
+/// order of the [`Phase`](super::schedule::Phase)s of a frame.
+///
+/// An error returned from a hook stops the game thread and is returned from
+/// [`run`](Runnable::run), as is a panic.
 #[allow(unused)]
 pub trait Runnable {
+  /// Events posted through an [`EventProxy`](super::event_proxy::EventProxy),
+  /// e.g. by background jobs.
+  type UserEvent: Send + Sync + 'static = ();
+
   fn new(foxy: &Foxy) -> Self;
 
-  fn start(&mut self, foxy: &Foxy) {}
+  fn start(&mut self, foxy: &Foxy) -> FoxyResult<()> {
+    Ok(())
+  }
 
-  fn fixed_update(&mut self, foxy: &Foxy, event: &FoxyEvent) {}
+  /// Called once per fixed tick. Each event is delivered to exactly one tick,
+  /// so later ticks in the same frame usually see an empty slice.
+  fn fixed_update(&mut self, foxy: &Foxy, events: &[FoxyEvent]) -> FoxyResult<()> {
+    Ok(())
+  }
 
-  fn input(&mut self, foxy: &Foxy, event: &InputEvent) {}
+  /// Called for each input event of the frame, in order, before the fixed
+  /// ticks.
+  fn input(&mut self, foxy: &Foxy, event: &InputEvent) -> FoxyResult<()> {
+    Ok(())
+  }
 
-  fn update(&mut self, foxy: &Foxy, event: &FoxyEvent) {}
+  /// Called once per frame with every event received since the last frame.
+  fn update(&mut self, foxy: &Foxy, events: &[FoxyEvent]) -> FoxyResult<()> {
+    Ok(())
+  }
 
-  fn late_update(&mut self, foxy: &Foxy, event: &FoxyEvent) {}
+  /// Called once per frame after `update`, `window` and the systems in
+  /// [`Phase::Update`](super::schedule::Phase::Update), e.g. for a camera that
+  /// follows whatever moved this frame.
+  fn late_update(&mut self, foxy: &Foxy, events: &[FoxyEvent]) -> FoxyResult<()> {
+    Ok(())
+  }
 
-  fn window(&mut self, foxy: &Foxy, event: &WindowEvent) {}
+  /// Called for each window event of the frame, in order, after `update`.
+  fn window(&mut self, foxy: &Foxy, event: &WindowEvent) -> FoxyResult<()> {
+    Ok(())
+  }
 
-  fn gui(&mut self, foxy: &Foxy, egui: &Context) {}
+  /// Called for each user event, in the order they were sent, before the
+  /// frame that follows them.
+  fn user_event(&mut self, foxy: &Foxy, event: Self::UserEvent) -> FoxyResult<()> {
+    Ok(())
+  }
+
+  fn gui(&mut self, foxy: &Foxy, egui: &Context) -> FoxyResult<()> {
+    Ok(())
+  }
+
+  /// Called when the window is hidden: minimized, fully covered, or the app
+  /// sent to the background by the OS. A good time to save and to mute audio.
+  /// Whether updates keep running is up to the
+  /// [`LifecyclePolicy`](super::builder::LifecyclePolicy).
+  fn suspended(&mut self, foxy: &Foxy) -> FoxyResult<()> {
+    Ok(())
+  }
+
+  /// Called when the window is shown again after
+  /// [`suspended`](Runnable::suspended).
+  fn resumed(&mut self, foxy: &Foxy) -> FoxyResult<()> {
+    Ok(())
+  }
 
   fn stop(&mut self, foxy: &Foxy) -> Flow {
     Flow::Exit
@@ -51,6 +143,6 @@ pub trait Runnable {
   where
     Self: Sized,
   {
-    Framework::new::<Self>(Self::settings())?.run()
+    Framework::<Self::UserEvent>::with_events::<Self>(Self::settings())?.run()
   }
 }
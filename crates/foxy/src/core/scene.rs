@@ -0,0 +1,308 @@
+use std::{any::Any, marker::PhantomData};
+
+use egui::Context;
+use tracing::*;
+
+use super::{
+  event::{FoxyEvent, InputEvent, WindowEvent},
+  foxy_state::Foxy,
+  runnable::{Flow, Runnable},
+  FoxyResult,
+};
+
+/// One screen or mode of a game, e.g. the main menu, gameplay or a pause
+/// overlay, run by a [`SceneStack`].
+///
+/// The frame hooks return a [`Flow`] to change scenes: [`Flow::Push`] covers
+/// this scene with another, [`Flow::Pop`] returns to the scene below,
+/// [`Flow::Replace`] swaps the top scene out and [`Flow::Exit`] ends the
+/// game. Transitions always act on the top of the stack, and are applied in
+/// order once every scene has run the hook.
+#[allow(unused)]
+pub trait Scene: 'static {
+  fn name(&self) -> &str {
+    std::any::type_name::<Self>()
+  }
+
+  /// Called when the scene is pushed onto the stack.
+  fn enter(&mut self, foxy: &Foxy) -> FoxyResult<()> {
+    Ok(())
+  }
+
+  /// Called when the scene is popped or replaced, and for every scene left
+  /// when the game exits, top first.
+  fn exit(&mut self, foxy: &Foxy) -> FoxyResult<()> {
+    Ok(())
+  }
+
+  /// Called when another scene is pushed on top of this one.
+  fn covered(&mut self, foxy: &Foxy) -> FoxyResult<()> {
+    Ok(())
+  }
+
+  /// Called when the scene on top of this one is popped.
+  fn uncovered(&mut self, foxy: &Foxy) -> FoxyResult<()> {
+    Ok(())
+  }
+
+  /// Whether the scene below keeps running its update hooks while this one
+  /// is on top, e.g. `true` for a HUD and `false` for a pause menu.
+  fn updates_below(&self) -> bool {
+    false
+  }
+
+  /// Whether the scene below keeps drawing its [`gui`](Scene::gui) under
+  /// this one, e.g. `true` for a pause menu over a frozen game.
+  fn draws_below(&self) -> bool {
+    false
+  }
+
+  fn fixed_update(&mut self, foxy: &Foxy, events: &[FoxyEvent]) -> FoxyResult<Flow> {
+    Ok(Flow::Continue)
+  }
+
+  fn input(&mut self, foxy: &Foxy, event: &InputEvent) -> FoxyResult<Flow> {
+    Ok(Flow::Continue)
+  }
+
+  fn update(&mut self, foxy: &Foxy, events: &[FoxyEvent]) -> FoxyResult<Flow> {
+    Ok(Flow::Continue)
+  }
+
+  fn late_update(&mut self, foxy: &Foxy, events: &[FoxyEvent]) -> FoxyResult<Flow> {
+    Ok(Flow::Continue)
+  }
+
+  fn window(&mut self, foxy: &Foxy, event: &WindowEvent) -> FoxyResult<Flow> {
+    Ok(Flow::Continue)
+  }
+
+  fn gui(&mut self, foxy: &Foxy, egui: &Context) -> FoxyResult<Flow> {
+    Ok(Flow::Continue)
+  }
+
+  /// Called on the top scene for each user event sent to the
+  /// [`SceneStack`]. `event` is the stack's `E`, recovered with
+  /// `event.downcast_ref::<E>()`.
+  fn user_event(&mut self, foxy: &Foxy, event: &dyn Any) -> FoxyResult<Flow> {
+    Ok(Flow::Continue)
+  }
+
+  /// See [`Runnable::suspended`]. Called on every scene in the stack.
+  fn suspended(&mut self, foxy: &Foxy) -> FoxyResult<()> {
+    Ok(())
+  }
+
+  /// See [`Runnable::resumed`]. Called on every scene in the stack.
+  fn resumed(&mut self, foxy: &Foxy) -> FoxyResult<()> {
+    Ok(())
+  }
+
+  /// Called on the top scene when the window is asked to close.
+  /// [`Flow::Exit`] closes it, [`Flow::Restart`] restarts the game, and
+  /// anything else keeps it running, so a scene can e.g. push a confirmation
+  /// dialog instead.
+  fn stop(&mut self, foxy: &Foxy) -> Flow {
+    Flow::Exit
+  }
+}
+
+/// A [`Runnable`] that runs a stack of [`Scene`]s, starting with `S`. `E` is
+/// its [`UserEvent`](Runnable::UserEvent) type.
+///
+/// Each frame the top scene runs, along with the scenes below it for as long
+/// as the scenes above let them through [`Scene::updates_below`] and
+/// [`Scene::draws_below`]. Scenes run bottom to top, so overlays draw over
+/// what they cover. The game exits once the last scene is popped.
+///
+/// ```ignore
+/// fn main() -> FoxyResult<()> {
+///   Framework::new::<SceneStack<MainMenu>>(FoxyCreateInfo::default().with_title("Foxy".into()))?.run()
+/// }
+/// ```
+pub struct SceneStack<S: Scene + Default, E: Send + Sync + 'static = ()> {
+  scenes: Vec<Box<dyn Scene>>,
+  _first: PhantomData<(S, E)>,
+}
+
+impl<S: Scene + Default, E: Send + Sync + 'static> std::fmt::Debug for SceneStack<S, E> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let scenes: Vec<_> = self.scenes.iter().map(|scene| scene.name()).collect();
+    f.debug_struct("SceneStack").field("scenes", &scenes).finish()
+  }
+}
+
+impl<S: Scene + Default, E: Send + Sync + 'static> SceneStack<S, E> {
+  pub fn len(&self) -> usize {
+    self.scenes.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.scenes.is_empty()
+  }
+
+  pub fn top(&self) -> Option<&dyn Scene> {
+    self.scenes.last().map(|scene| &**scene)
+  }
+
+  /// Runs `hook` on every scene that is updating, or drawing if `draw`, then
+  /// applies the transitions they returned.
+  fn run(
+    &mut self,
+    foxy: &Foxy,
+    draw: bool,
+    mut hook: impl FnMut(&mut dyn Scene) -> FoxyResult<Flow>,
+  ) -> FoxyResult<()> {
+    let mut first = self.scenes.len().saturating_sub(1);
+    while first > 0 {
+      let above = &self.scenes[first];
+      match draw {
+        true if above.draws_below() => first -= 1,
+        false if above.updates_below() => first -= 1,
+        _ => break,
+      }
+    }
+
+    let mut flows = Vec::new();
+    for scene in &mut self.scenes[first..] {
+      flows.push(hook(&mut **scene)?);
+    }
+
+    flows.into_iter().try_for_each(|flow| self.apply(foxy, flow))
+  }
+
+  fn apply(&mut self, foxy: &Foxy, flow: Flow) -> FoxyResult<()> {
+    // Left over from a transition that already ended the game
+    if self.scenes.is_empty() {
+      return Ok(());
+    }
+
+    match flow {
+      Flow::Continue => (),
+      Flow::Push(mut scene) => {
+        if let Some(top) = self.scenes.last_mut() {
+          top.covered(foxy)?;
+        }
+        scene.enter(foxy)?;
+        self.scenes.push(scene);
+      }
+      Flow::Pop => {
+        if let Some(mut top) = self.scenes.pop() {
+          top.exit(foxy)?;
+        }
+        if let Some(top) = self.scenes.last_mut() {
+          top.uncovered(foxy)?;
+        }
+      }
+      Flow::Replace(mut scene) => {
+        if let Some(mut top) = self.scenes.pop() {
+          top.exit(foxy)?;
+        }
+        scene.enter(foxy)?;
+        self.scenes.push(scene);
+      }
+      Flow::Exit => self.exit_all(foxy)?,
+      Flow::Restart => {
+        // Exited here rather than in `stop` so the scenes see a normal exit
+        self.exit_all(foxy)?;
+        foxy.read().request_restart();
+        return Ok(());
+      }
+    }
+
+    if self.scenes.is_empty() {
+      foxy.read().request_exit();
+    }
+
+    Ok(())
+  }
+
+  fn exit_all(&mut self, foxy: &Foxy) -> FoxyResult<()> {
+    while let Some(mut scene) = self.scenes.pop() {
+      scene.exit(foxy)?;
+    }
+
+    Ok(())
+  }
+}
+
+impl<S: Scene + Default, E: Send + Sync + 'static> Runnable for SceneStack<S, E> {
+  type UserEvent = E;
+
+  fn new(_foxy: &Foxy) -> Self {
+    Self {
+      scenes: Vec::new(),
+      _first: PhantomData,
+    }
+  }
+
+  fn start(&mut self, foxy: &Foxy) -> FoxyResult<()> {
+    let mut first = S::default();
+    first.enter(foxy)?;
+    self.scenes.push(Box::new(first));
+    Ok(())
+  }
+
+  fn fixed_update(&mut self, foxy: &Foxy, events: &[FoxyEvent]) -> FoxyResult<()> {
+    self.run(foxy, false, |scene| scene.fixed_update(foxy, events))
+  }
+
+  fn input(&mut self, foxy: &Foxy, event: &InputEvent) -> FoxyResult<()> {
+    self.run(foxy, false, |scene| scene.input(foxy, event))
+  }
+
+  fn update(&mut self, foxy: &Foxy, events: &[FoxyEvent]) -> FoxyResult<()> {
+    self.run(foxy, false, |scene| scene.update(foxy, events))
+  }
+
+  fn late_update(&mut self, foxy: &Foxy, events: &[FoxyEvent]) -> FoxyResult<()> {
+    self.run(foxy, false, |scene| scene.late_update(foxy, events))
+  }
+
+  fn window(&mut self, foxy: &Foxy, event: &WindowEvent) -> FoxyResult<()> {
+    self.run(foxy, false, |scene| scene.window(foxy, event))
+  }
+
+  fn gui(&mut self, foxy: &Foxy, egui: &Context) -> FoxyResult<()> {
+    self.run(foxy, true, |scene| scene.gui(foxy, egui))
+  }
+
+  fn user_event(&mut self, foxy: &Foxy, event: E) -> FoxyResult<()> {
+    let Some(top) = self.scenes.last_mut() else {
+      return Ok(());
+    };
+
+    let flow = top.user_event(foxy, &event)?;
+    self.apply(foxy, flow)
+  }
+
+  fn suspended(&mut self, foxy: &Foxy) -> FoxyResult<()> {
+    self.scenes.iter_mut().try_for_each(|scene| scene.suspended(foxy))
+  }
+
+  fn resumed(&mut self, foxy: &Foxy) -> FoxyResult<()> {
+    self.scenes.iter_mut().try_for_each(|scene| scene.resumed(foxy))
+  }
+
+  fn stop(&mut self, foxy: &Foxy) -> Flow {
+    let Some(top) = self.scenes.last_mut() else {
+      return Flow::Exit;
+    };
+
+    // `stop` can't return errors, so failing hooks are only logged
+    match top.stop(foxy) {
+      Flow::Exit => {
+        if let Err(error) = self.exit_all(foxy) {
+          error!("{error}");
+        }
+        Flow::Exit
+      }
+      flow => {
+        if let Err(error) = self.apply(foxy, flow) {
+          error!("{error}");
+        }
+        Flow::Continue
+      }
+    }
+  }
+}
@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+
+use super::{ecs::WorldSystem, event::FoxyEvent, foxy_state::Foxy, plugin::Plugin, FoxyResult};
+
+/// The parts of a frame on the game thread, in the order they run. Each phase
+/// calls its [`Runnable`](super::runnable::Runnable) hooks first, then its
+/// [`Plugin`] hooks, then the systems added to it with
+/// [`Schedule::add_system`] and [`Schedule::add_world_system`], each in the
+/// order they were added.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub enum Phase {
+  /// [`Runnable::input`](super::runnable::Runnable::input) for each input
+  /// event. Runs once per frame.
+  PreUpdate,
+  /// [`Runnable::fixed_update`](super::runnable::Runnable::fixed_update).
+  /// Runs once per fixed tick, so zero or more times per frame, and systems
+  /// get the events of that tick.
+  Fixed,
+  /// [`Runnable::update`](super::runnable::Runnable::update), then
+  /// [`Runnable::window`](super::runnable::Runnable::window) for each window
+  /// event.
+  Update,
+  /// [`Runnable::late_update`](super::runnable::Runnable::late_update), for
+  /// work that depends on everything having moved, e.g. a camera following
+  /// the player.
+  LateUpdate,
+  /// [`Runnable::gui`](super::runnable::Runnable::gui). Systems can draw with
+  /// [`State::egui`](super::foxy_state::State::egui).
+  Gui,
+  /// Last chance to change anything before the frame is handed to the
+  /// renderer.
+  PreRender,
+}
+
+impl Phase {
+  pub const ORDER: [Phase; 6] = [
+    Phase::PreUpdate,
+    Phase::Fixed,
+    Phase::Update,
+    Phase::LateUpdate,
+    Phase::Gui,
+    Phase::PreRender,
+  ];
+}
+
+/// Work to run in a [`Phase`], with that phase's events.
+pub type System = Box<dyn FnMut(&Foxy, &[FoxyEvent]) -> FoxyResult<()> + Send>;
+
+enum Entry {
+  Plain(System),
+  World(WorldSystem),
+}
+
+/// Plugins and systems to run each frame besides the app's own hooks. Build
+/// one up with
+/// [`FoxyCreateInfo::with_system`](super::builder::FoxyCreateInfo::with_system),
+/// [`FoxyCreateInfo::with_world_system`](super::builder::FoxyCreateInfo::with_world_system)
+/// and [`FoxyCreateInfo::with_plugin`](super::builder::FoxyCreateInfo::with_plugin).
+#[derive(Default)]
+pub struct Schedule {
+  plugins: Vec<Box<dyn Plugin>>,
+  systems: HashMap<Phase, Vec<Entry>>,
+  parallel: bool,
+}
+
+impl std::fmt::Debug for Schedule {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let counts: HashMap<_, _> = self
+      .systems
+      .iter()
+      .map(|(phase, systems)| (phase, systems.len()))
+      .collect();
+    let plugins: Vec<_> = self.plugins.iter().map(|plugin| plugin.name()).collect();
+    f.debug_struct("Schedule")
+      .field("plugins", &plugins)
+      .field("systems", &counts)
+      .field("parallel", &self.parallel)
+      .finish()
+  }
+}
+
+impl Schedule {
+  pub fn add_system(
+    &mut self,
+    phase: Phase,
+    system: impl FnMut(&Foxy, &[FoxyEvent]) -> FoxyResult<()> + Send + 'static,
+  ) -> &mut Self {
+    self
+      .systems
+      .entry(phase)
+      .or_default()
+      .push(Entry::Plain(Box::new(system)));
+    self
+  }
+
+  pub fn add_world_system(&mut self, phase: Phase, system: WorldSystem) -> &mut Self {
+    self.systems.entry(phase).or_default().push(Entry::World(system));
+    self
+  }
+
+  /// Runs neighbouring [`WorldSystem`]s whose access doesn't conflict on
+  /// separate threads. Systems still start in the order they were added,
+  /// and one that conflicts with an earlier system waits for it. Plain
+  /// systems always run alone. Off by default, since spawning threads only
+  /// pays off for heavy systems.
+  pub fn set_parallel(&mut self, parallel: bool) -> &mut Self {
+    self.parallel = parallel;
+    self
+  }
+
+  pub fn add_plugin(&mut self, plugin: impl Plugin) -> &mut Self {
+    self.plugins.push(Box::new(plugin));
+    self
+  }
+
+  pub fn system_count(&self, phase: Phase) -> usize {
+    self.systems.get(&phase).map_or(0, Vec::len)
+  }
+
+  /// Runs the plugin hooks and then the systems of `phase` in order, stopping
+  /// at the first error. Changes deferred on the world are applied after
+  /// each step.
+  pub(crate) fn run(&mut self, phase: Phase, foxy: &Foxy, events: &[FoxyEvent]) -> FoxyResult<()> {
+    match phase {
+      Phase::Update => {
+        for plugin in &mut self.plugins {
+          plugin.update(foxy, events)?;
+        }
+      }
+      Phase::Gui => {
+        let egui = foxy.read().egui().clone();
+        for plugin in &mut self.plugins {
+          plugin.gui(foxy, &egui)?;
+        }
+      }
+      _ => (),
+    }
+    foxy.world().apply_deferred();
+
+    let Some(systems) = self.systems.get_mut(&phase) else {
+      return Ok(());
+    };
+
+    let mut next = 0;
+    while next < systems.len() {
+      let batch = match self.parallel {
+        true => parallel_batch(&mut systems[next..]),
+        false => Vec::new(),
+      };
+
+      let ran = batch.len().max(1);
+      if batch.len() > 1 {
+        run_parallel(batch, foxy, events)?;
+      } else {
+        match &mut systems[next] {
+          Entry::Plain(system) => system(foxy, events)?,
+          Entry::World(system) => system.run(foxy, events)?,
+        }
+      }
+
+      foxy.world().apply_deferred();
+      next += ran;
+    }
+
+    Ok(())
+  }
+
+  /// Calls [`Plugin::build`] on every plugin, in order. Plugins added while
+  /// building go after the one that added them and are built in turn.
+  pub(crate) fn build_plugins(&mut self, foxy: &Foxy) -> FoxyResult<()> {
+    let mut plugins = std::mem::take(&mut self.plugins);
+    let mut next = 0;
+    while let Some(plugin) = plugins.get_mut(next) {
+      plugin.build(foxy, self)?;
+      let added = std::mem::take(&mut self.plugins);
+      plugins.splice(next + 1..next + 1, added);
+      next += 1;
+    }
+
+    self.plugins = plugins;
+    Ok(())
+  }
+
+  pub(crate) fn start_plugins(&mut self, foxy: &Foxy) -> FoxyResult<()> {
+    self.plugins.iter_mut().try_for_each(|plugin| plugin.start(foxy))
+  }
+
+  pub(crate) fn stop_plugins(&mut self, foxy: &Foxy) {
+    for plugin in self.plugins.iter_mut().rev() {
+      plugin.stop(foxy);
+    }
+  }
+}
+
+/// The leading `systems` that can run at once: world systems whose access
+/// doesn't conflict with any before them in the batch.
+fn parallel_batch(systems: &mut [Entry]) -> Vec<&mut WorldSystem> {
+  let mut batch: Vec<&mut WorldSystem> = Vec::new();
+  for entry in systems {
+    match entry {
+      Entry::World(system) if batch.iter().all(|other| other.access().is_compatible(system.access())) => {
+        batch.push(system)
+      }
+      _ => break,
+    }
+  }
+
+  batch
+}
+
+fn run_parallel(batch: Vec<&mut WorldSystem>, foxy: &Foxy, events: &[FoxyEvent]) -> FoxyResult<()> {
+  std::thread::scope(|scope| {
+    let handles: Vec<_> = batch
+      .into_iter()
+      .map(|system| scope.spawn(|| system.run(foxy, events)))
+      .collect();
+
+    // Joined in order so the reported error doesn't depend on timing. A
+    // panic carries on to the game thread like any other
+    handles.into_iter().try_for_each(|handle| {
+      handle
+        .join()
+        .unwrap_or_else(|payload| std::panic::resume_unwind(payload))
+    })
+  })
+}
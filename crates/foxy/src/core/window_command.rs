@@ -0,0 +1,188 @@
+use winit::{
+  dpi::{PhysicalPosition, PhysicalSize, Position, Size},
+  monitor::MonitorHandle,
+  window::{CursorGrabMode, CursorIcon, Fullscreen, UserAttentionType, Window},
+};
+
+use super::{event::WindowEvent, FoxyResult};
+use crate::foxy_error;
+
+/// A request to change the window, sent from the game thread with
+/// [`State::send_window_command`](super::foxy_state::State::send_window_command)
+/// and carried out on the main thread before the next frame is drawn.
+///
+/// Commands that fail come back as [`WindowEvent::CommandFailed`]. Commands
+/// that change the window produce the usual events, e.g.
+/// [`WindowEvent::Resized`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum WindowCommand {
+  SetFullscreen(FullscreenMode),
+  SetSize(Size),
+  SetPosition(Position),
+  SetTitle(String),
+  SetCursorVisible(bool),
+  SetCursorGrab(CursorGrab),
+  SetCursorIcon(CursorIcon),
+  SetMinimized(bool),
+  /// Flashes the taskbar entry or bounces the dock icon. `None` cancels a
+  /// previous request.
+  RequestAttention(Option<UserAttentionType>),
+  /// See [`State::set_ime_allowed`](super::foxy_state::State::set_ime_allowed).
+  SetImeAllowed(bool),
+  SetImeCursorArea {
+    position: Position,
+    size: Size,
+  },
+  /// Answered with [`WindowEvent::Monitors`].
+  QueryMonitors,
+}
+
+/// Identifies a monitor by its position in the list sent with
+/// [`WindowEvent::Monitors`]. Query again after monitors are plugged in or
+/// removed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct MonitorId(pub usize);
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FullscreenMode {
+  Windowed,
+  /// Fullscreen at the desktop resolution. `None` picks the monitor the
+  /// window is on.
+  Borderless(Option<MonitorId>),
+  /// Changes the monitor's resolution. `video_mode` indexes into
+  /// [`MonitorInfo::video_modes`].
+  Exclusive {
+    monitor: MonitorId,
+    video_mode: usize,
+  },
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum CursorGrab {
+  None,
+  /// Keeps the cursor inside the window. Not supported on macOS.
+  Confined,
+  /// Keeps the cursor in place, for camera controls. Not supported on
+  /// Windows and X11.
+  Locked,
+}
+
+impl From<CursorGrab> for CursorGrabMode {
+  fn from(value: CursorGrab) -> Self {
+    match value {
+      CursorGrab::None => CursorGrabMode::None,
+      CursorGrab::Confined => CursorGrabMode::Confined,
+      CursorGrab::Locked => CursorGrabMode::Locked,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitorInfo {
+  pub id: MonitorId,
+  pub name: Option<String>,
+  pub size: PhysicalSize<u32>,
+  pub position: PhysicalPosition<i32>,
+  pub scale_factor: f64,
+  pub refresh_rate_millihertz: Option<u32>,
+  pub video_modes: Vec<VideoModeInfo>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct VideoModeInfo {
+  pub size: PhysicalSize<u32>,
+  pub bit_depth: u16,
+  pub refresh_rate_millihertz: u32,
+}
+
+impl WindowCommand {
+  /// Carries out the command. Must be called on the main thread.
+  pub(crate) fn apply(self, window: &Window) -> FoxyResult<Option<WindowEvent>> {
+    match self {
+      WindowCommand::SetFullscreen(mode) => window.set_fullscreen(fullscreen(window, mode)?),
+      WindowCommand::SetSize(size) => {
+        if let Some(physical) = window.request_inner_size(size) {
+          return Ok(Some(WindowEvent::Resized {
+            physical,
+            logical: physical.to_logical(window.scale_factor()),
+          }));
+        }
+      }
+      WindowCommand::SetPosition(position) => window.set_outer_position(position),
+      WindowCommand::SetTitle(title) => window.set_title(&title),
+      WindowCommand::SetCursorVisible(visible) => window.set_cursor_visible(visible),
+      WindowCommand::SetCursorGrab(grab) => window.set_cursor_grab(grab.into())?,
+      WindowCommand::SetCursorIcon(icon) => window.set_cursor_icon(icon),
+      WindowCommand::SetMinimized(minimized) => window.set_minimized(minimized),
+      WindowCommand::RequestAttention(attention) => window.request_user_attention(attention),
+      WindowCommand::SetImeAllowed(allowed) => window.set_ime_allowed(allowed),
+      WindowCommand::SetImeCursorArea { position, size } => window.set_ime_cursor_area(position, size),
+      WindowCommand::QueryMonitors => return Ok(Some(monitors(window))),
+    }
+
+    Ok(None)
+  }
+}
+
+fn monitors(window: &Window) -> WindowEvent {
+  let current = window.current_monitor();
+  let mut current_id = None;
+
+  let monitors = window
+    .available_monitors()
+    .enumerate()
+    .map(|(index, monitor)| {
+      let id = MonitorId(index);
+      if current.as_ref() == Some(&monitor) {
+        current_id = Some(id);
+      }
+
+      MonitorInfo {
+        id,
+        name: monitor.name(),
+        size: monitor.size(),
+        position: monitor.position(),
+        scale_factor: monitor.scale_factor(),
+        refresh_rate_millihertz: monitor.refresh_rate_millihertz(),
+        video_modes: monitor
+          .video_modes()
+          .map(|mode| VideoModeInfo {
+            size: mode.size(),
+            bit_depth: mode.bit_depth(),
+            refresh_rate_millihertz: mode.refresh_rate_millihertz(),
+          })
+          .collect(),
+      }
+    })
+    .collect();
+
+  WindowEvent::Monitors {
+    monitors,
+    current: current_id,
+  }
+}
+
+fn monitor(window: &Window, id: MonitorId) -> FoxyResult<MonitorHandle> {
+  window
+    .available_monitors()
+    .nth(id.0)
+    .ok_or_else(|| foxy_error!("no monitor {}", id.0))
+}
+
+fn fullscreen(window: &Window, mode: FullscreenMode) -> FoxyResult<Option<Fullscreen>> {
+  Ok(match mode {
+    FullscreenMode::Windowed => None,
+    FullscreenMode::Borderless(None) => Some(Fullscreen::Borderless(None)),
+    FullscreenMode::Borderless(Some(id)) => Some(Fullscreen::Borderless(Some(monitor(window, id)?))),
+    FullscreenMode::Exclusive {
+      monitor: id,
+      video_mode,
+    } => {
+      let mode = monitor(window, id)?
+        .video_modes()
+        .nth(video_mode)
+        .ok_or_else(|| foxy_error!("monitor {} has no video mode {video_mode}", id.0))?;
+      Some(Fullscreen::Exclusive(mode))
+    }
+  })
+}
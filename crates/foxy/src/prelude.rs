@@ -1,11 +1,21 @@
 pub use foxy_utils::log::prelude::*;
 
 pub use crate::core::{
-  builder::{DebugInfo, FoxyCreateInfo, Polling},
-  foxy_state::Foxy,
-  event::{FoxyEvent, InputEvent, WindowEvent},
+  builder::{DebugInfo, FoxyCreateInfo, LifecyclePolicy, Polling},
+  clipboard::{Clipboard, ClipboardImage},
+  drag_drop::{AssetKind, DragDropEvent},
+  ecs::{Added, Changed, Entity, Mut, Query, SystemContext, With, Without, World, WorldSystem},
+  event::{FoxyEvent, ImeEvent, InputEvent, WindowEvent},
+  event_proxy::EventProxy,
   foxy_loop::Framework,
+  foxy_state::Foxy,
+  headless::Headless,
   message::RenderLoopMessage,
-  runnable::Runnable,
+  plugin::Plugin,
+  resources::{Res, ResMut},
+  runnable::{Flow, Runnable},
+  scene::{Scene, SceneStack},
+  schedule::Phase,
+  window_command::WindowCommand,
   FoxyResult,
 };
@@ -1,12 +1,48 @@
+#[cfg(target_os = "windows")]
+use winit::platform::windows::EventLoopBuilderExtWindows;
+#[cfg(target_os = "linux")]
+use winit::platform::{
+  wayland::{EventLoopBuilderExtWayland, WindowBuilderExtWayland},
+  x11::{EventLoopBuilderExtX11, WindowBuilderExtX11},
+};
 use winit::{
   dpi::{Position, Size},
   event_loop::{EventLoop, EventLoopBuilder},
-  platform::windows::EventLoopBuilderExtWindows,
   window::{Fullscreen, Icon, Theme, Window, WindowBuilder, WindowButtons},
 };
 
 use crate::core::FoxyResult;
 
+/// Display server used by the event loop on Linux.
+#[cfg(target_os = "linux")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LinuxBackend {
+  /// Let winit decide, preferring Wayland when `WAYLAND_DISPLAY` is set.
+  #[default]
+  Auto,
+  X11,
+  Wayland,
+}
+
+/// Application identity reported to the window manager. This becomes the
+/// `app_id` on Wayland and the `WM_CLASS` class/instance pair on X11.
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppId {
+  pub general: String,
+  pub instance: String,
+}
+
+#[cfg(target_os = "linux")]
+impl AppId {
+  pub fn new(general: impl Into<String>, instance: impl Into<String>) -> Self {
+    Self {
+      general: general.into(),
+      instance: instance.into(),
+    }
+  }
+}
+
 #[derive(Debug, Clone)]
 pub struct WindowCreateInfo {
   pub inner_size: Option<Size>,
@@ -25,6 +61,10 @@ pub struct WindowCreateInfo {
   pub preferred_theme: Option<Theme>,
   pub resize_increments: Option<Size>,
   pub fullscreen: Option<Fullscreen>,
+  #[cfg(target_os = "linux")]
+  pub linux_backend: LinuxBackend,
+  #[cfg(target_os = "linux")]
+  pub app_id: Option<AppId>,
 }
 
 impl Default for WindowCreateInfo {
@@ -46,13 +86,17 @@ impl Default for WindowCreateInfo {
       window_icon: None,
       preferred_theme: None,
       resize_increments: None,
+      #[cfg(target_os = "linux")]
+      linux_backend: LinuxBackend::Auto,
+      #[cfg(target_os = "linux")]
+      app_id: None,
     }
   }
 }
 
 impl WindowCreateInfo {
   pub fn create_window<T>(&self) -> FoxyResult<(EventLoop<T>, Window)> {
-    let event_loop = EventLoopBuilder::<T>::with_user_event().with_any_thread(true).build()?;
+    let event_loop = self.build_event_loop()?;
     let mut builder = WindowBuilder::new();
 
     if let Some(size) = self.inner_size {
@@ -87,8 +131,43 @@ impl WindowCreateInfo {
       .with_theme(self.preferred_theme)
       .with_fullscreen(self.fullscreen.clone());
 
+    #[cfg(target_os = "linux")]
+    if let Some(app_id) = &self.app_id {
+      builder = match self.linux_backend {
+        LinuxBackend::Wayland => {
+          WindowBuilderExtWayland::with_name(builder, app_id.general.clone(), app_id.instance.clone())
+        }
+        LinuxBackend::X11 | LinuxBackend::Auto => {
+          WindowBuilderExtX11::with_name(builder, app_id.general.clone(), app_id.instance.clone())
+        }
+      };
+    }
+
     let window = builder.build(&event_loop)?;
 
     Ok((event_loop, window))
   }
+
+  fn build_event_loop<T>(&self) -> FoxyResult<EventLoop<T>> {
+    #[cfg_attr(not(any(target_os = "windows", target_os = "linux")), allow(unused_mut))]
+    let mut builder = EventLoopBuilder::<T>::with_user_event();
+
+    #[cfg(target_os = "windows")]
+    builder.with_any_thread(true);
+
+    #[cfg(target_os = "linux")]
+    match self.linux_backend {
+      LinuxBackend::Auto => {
+        EventLoopBuilderExtX11::with_any_thread(&mut builder, true);
+      }
+      LinuxBackend::X11 => {
+        EventLoopBuilderExtX11::with_any_thread(builder.with_x11(), true);
+      }
+      LinuxBackend::Wayland => {
+        EventLoopBuilderExtWayland::with_any_thread(builder.with_wayland(), true);
+      }
+    }
+
+    Ok(builder.build()?)
+  }
 }
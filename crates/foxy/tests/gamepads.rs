@@ -0,0 +1,73 @@
+use foxy::{
+  core::input::gamepad::{GamepadAxis, GamepadButton, Stick, VirtualGamepads},
+  prelude::*,
+};
+
+/// Counts button edges, which are only visible during the frame they happen in.
+#[derive(Default)]
+struct Edges {
+  presses: u32,
+  releases: u32,
+}
+
+impl Runnable for Edges {
+  fn new(_foxy: &Foxy) -> Self {
+    Self::default()
+  }
+
+  fn update(&mut self, foxy: &Foxy, _events: &[FoxyEvent]) -> FoxyResult<()> {
+    let foxy = foxy.read();
+    self.presses += foxy.input().any_gamepad_just_pressed(GamepadButton::South) as u32;
+    self.releases += foxy.input().any_gamepad_just_released(GamepadButton::South) as u32;
+    Ok(())
+  }
+}
+
+fn headless(pads: &VirtualGamepads) -> Headless<Edges> {
+  Framework::headless::<Edges>(FoxyCreateInfo::default().with_gamepads(pads.clone())).with_manual_clock()
+}
+
+#[test]
+fn virtual_buttons_reach_input() {
+  let pads = VirtualGamepads::default();
+  let mut headless = headless(&pads);
+  let id = pads.connect("Test pad");
+  headless.step().unwrap();
+  {
+    let foxy = headless.foxy().read();
+    assert_eq!(foxy.input().gamepads().collect::<Vec<_>>(), vec![id]);
+    assert_eq!(foxy.input().gamepad_name(id), Some("Test pad"));
+  }
+
+  pads.press(id, GamepadButton::South);
+  headless.step().unwrap();
+  headless.step().unwrap();
+  assert!(headless.foxy().read().input().pressed((id, GamepadButton::South)));
+  assert_eq!(headless.app().unwrap().presses, 1);
+
+  pads.release(id, GamepadButton::South);
+  headless.step().unwrap();
+  assert!(!headless.foxy().read().input().any_gamepad_pressed(GamepadButton::South));
+  assert_eq!(headless.app().unwrap().releases, 1);
+}
+
+#[test]
+fn virtual_axes_go_through_dead_zones() {
+  let pads = VirtualGamepads::default();
+  let mut headless = headless(&pads);
+  let id = pads.connect("Test pad");
+  pads.set_stick(id, Stick::Left, (0.0, 1.0));
+  pads.set_axis(id, GamepadAxis::RightTrigger, 0.01);
+  headless.step().unwrap();
+  {
+    let foxy = headless.foxy().read();
+    assert_eq!(foxy.input().gamepad_stick(id, Stick::Left), (0.0, 1.0));
+    assert_eq!(foxy.input().gamepad_axis(id, GamepadAxis::RightTrigger), 0.0);
+  }
+
+  pads.disconnect(id);
+  headless.step().unwrap();
+  let foxy = headless.foxy().read();
+  assert_eq!(foxy.input().gamepads().count(), 0);
+  assert_eq!(foxy.input().any_gamepad_axis(GamepadAxis::LeftStickY), 0.0);
+}
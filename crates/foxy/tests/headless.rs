@@ -0,0 +1,163 @@
+use std::time::Duration;
+
+use foxy::prelude::*;
+
+/// One fixed tick at the default tick rate of 128 Hz.
+const TICK: Duration = Duration::from_nanos(7_812_500);
+
+#[derive(Default)]
+struct Log(Vec<&'static str>);
+
+/// How the app answers [`Runnable::stop`].
+#[derive(Clone, Copy, PartialEq)]
+enum OnStop {
+  Exit,
+  Continue,
+  RestartOnce,
+}
+
+struct App {
+  fixed_updates: u32,
+  updates: u32,
+}
+
+impl App {
+  fn log(foxy: &Foxy, entry: &'static str) {
+    if let Some(mut log) = foxy.resource_mut::<Log>() {
+      log.0.push(entry);
+    }
+  }
+}
+
+impl Runnable for App {
+  fn new(foxy: &Foxy) -> Self {
+    Self::log(foxy, "new");
+    Self {
+      fixed_updates: 0,
+      updates: 0,
+    }
+  }
+
+  fn start(&mut self, foxy: &Foxy) -> FoxyResult<()> {
+    Self::log(foxy, "start");
+    Ok(())
+  }
+
+  fn fixed_update(&mut self, _foxy: &Foxy, _events: &[FoxyEvent]) -> FoxyResult<()> {
+    self.fixed_updates += 1;
+    Ok(())
+  }
+
+  fn update(&mut self, _foxy: &Foxy, _events: &[FoxyEvent]) -> FoxyResult<()> {
+    self.updates += 1;
+    Ok(())
+  }
+
+  fn stop(&mut self, foxy: &Foxy) -> Flow {
+    Self::log(foxy, "stop");
+    let on_stop = foxy.resource::<OnStop>().map_or(OnStop::Exit, |on_stop| *on_stop);
+    match on_stop {
+      OnStop::Exit => Flow::Exit,
+      OnStop::Continue => Flow::Continue,
+      OnStop::RestartOnce => {
+        foxy.insert_resource(OnStop::Exit);
+        Flow::Restart
+      }
+    }
+  }
+
+  fn delete(self) {}
+}
+
+fn headless(on_stop: OnStop) -> Headless<App> {
+  let headless = Framework::headless::<App>(FoxyCreateInfo::default()).with_manual_clock();
+  headless.foxy().insert_resource(Log::default());
+  headless.foxy().insert_resource(on_stop);
+  headless
+}
+
+fn log(headless: &Headless<App>) -> Vec<&'static str> {
+  headless.foxy().resource::<Log>().unwrap().0.clone()
+}
+
+#[test]
+fn fixed_update_runs_once_per_elapsed_tick() {
+  let mut headless = headless(OnStop::Exit);
+  headless.step().unwrap();
+  assert_eq!(headless.app().unwrap().fixed_updates, 0);
+
+  headless.advance(TICK * 3);
+  headless.step().unwrap();
+  let app = headless.app().unwrap();
+  assert_eq!((app.fixed_updates, app.updates), (3, 2));
+
+  headless.advance(TICK / 2);
+  headless.step().unwrap();
+  headless.advance(TICK / 2);
+  headless.step().unwrap();
+  let app = headless.app().unwrap();
+  assert_eq!((app.fixed_updates, app.updates), (4, 4));
+}
+
+#[test]
+fn requested_exit_is_final() {
+  let mut headless = headless(OnStop::Exit);
+  headless.step().unwrap();
+  headless.foxy().read().request_exit();
+  headless.step().unwrap();
+  assert!(!headless.is_running());
+  assert!(headless.has_exited());
+
+  headless.step().unwrap();
+  headless.start().unwrap();
+  assert!(headless.app().is_none());
+  assert_eq!(log(&headless), ["new", "start"]);
+}
+
+#[test]
+fn stop_can_restart_then_exit() {
+  let mut headless = headless(OnStop::RestartOnce);
+  headless.advance(TICK);
+  headless.step().unwrap();
+  assert_eq!(headless.app().unwrap().fixed_updates, 1);
+
+  assert!(matches!(headless.stop().unwrap(), Flow::Restart));
+  let app = headless.app().unwrap();
+  assert_eq!((app.fixed_updates, app.updates), (0, 0));
+  assert!(!headless.has_exited());
+
+  assert!(headless.stop().unwrap().is_exit());
+  assert!(headless.has_exited());
+  headless.step().unwrap();
+  assert_eq!(log(&headless), ["new", "start", "stop", "new", "start", "stop"]);
+}
+
+#[test]
+fn requested_restart_replaces_the_app() {
+  let mut headless = headless(OnStop::Exit);
+  headless.step().unwrap();
+  headless.step().unwrap();
+  headless.foxy().read().request_restart();
+  headless.step().unwrap();
+
+  assert_eq!(headless.app().unwrap().updates, 0);
+  assert!(headless.is_running());
+  assert_eq!(log(&headless), ["new", "start", "stop", "new", "start"]);
+}
+
+#[test]
+fn run_while_stops_once_the_predicate_fails() {
+  let mut headless = headless(OnStop::Exit);
+  headless.run_while(|_, app| app.updates < 5).unwrap();
+  assert!(headless.has_exited());
+  assert_eq!(log(&headless), ["new", "start", "stop"]);
+}
+
+#[test]
+fn run_while_returns_when_the_stop_is_vetoed() {
+  let mut headless = headless(OnStop::Continue);
+  headless.run_while(|_, app| app.updates < 5).unwrap();
+  assert!(headless.is_running());
+  assert_eq!(headless.app().unwrap().updates, 5);
+  assert_eq!(log(&headless), ["new", "start", "stop"]);
+}
@@ -0,0 +1,108 @@
+use std::any::Any;
+
+use foxy::prelude::*;
+
+#[derive(Default)]
+struct Log(Vec<String>);
+
+enum Command {
+  Push(&'static str),
+  Pop,
+  Replace(&'static str),
+}
+
+/// Logs every transition hook it sees, and turns [`Command`]s into flows.
+struct Named(&'static str);
+
+impl Default for Named {
+  fn default() -> Self {
+    Self("menu")
+  }
+}
+
+impl Named {
+  fn log(&self, foxy: &Foxy, hook: &str) {
+    if let Some(mut log) = foxy.resource_mut::<Log>() {
+      log.0.push(format!("{} {hook}", self.0));
+    }
+  }
+}
+
+impl Scene for Named {
+  fn enter(&mut self, foxy: &Foxy) -> FoxyResult<()> {
+    self.log(foxy, "enter");
+    Ok(())
+  }
+
+  fn exit(&mut self, foxy: &Foxy) -> FoxyResult<()> {
+    self.log(foxy, "exit");
+    Ok(())
+  }
+
+  fn covered(&mut self, foxy: &Foxy) -> FoxyResult<()> {
+    self.log(foxy, "covered");
+    Ok(())
+  }
+
+  fn uncovered(&mut self, foxy: &Foxy) -> FoxyResult<()> {
+    self.log(foxy, "uncovered");
+    Ok(())
+  }
+
+  fn user_event(&mut self, foxy: &Foxy, event: &dyn Any) -> FoxyResult<Flow> {
+    self.log(foxy, "event");
+    Ok(match event.downcast_ref::<Command>() {
+      Some(Command::Push(name)) => Flow::push(Named(name)),
+      Some(Command::Pop) => Flow::Pop,
+      Some(Command::Replace(name)) => Flow::replace(Named(name)),
+      None => Flow::Continue,
+    })
+  }
+}
+
+type Stack = SceneStack<Named, Command>;
+
+/// Sends `command`, steps once and returns what was logged during the step.
+fn send(headless: &mut Headless<Stack>, command: Command) -> Vec<String> {
+  headless.event_proxy().send(command).unwrap();
+  headless.step().unwrap();
+  std::mem::take(&mut headless.foxy().resource_mut::<Log>().unwrap().0)
+}
+
+#[test]
+fn transitions_run_hooks_in_order() {
+  let mut headless = Framework::headless::<Stack>(FoxyCreateInfo::default()).with_manual_clock();
+  headless.foxy().insert_resource(Log::default());
+  headless.step().unwrap();
+  assert_eq!(std::mem::take(&mut headless.foxy().resource_mut::<Log>().unwrap().0), [
+    "menu enter"
+  ]);
+
+  assert_eq!(send(&mut headless, Command::Push("game")), [
+    "menu event",
+    "menu covered",
+    "game enter"
+  ]);
+  assert_eq!(send(&mut headless, Command::Push("pause")), [
+    "game event",
+    "game covered",
+    "pause enter"
+  ]);
+  assert_eq!(send(&mut headless, Command::Replace("options")), [
+    "pause event",
+    "pause exit",
+    "options enter"
+  ]);
+  assert_eq!(headless.app().unwrap().len(), 3);
+
+  assert_eq!(send(&mut headless, Command::Pop), [
+    "options event",
+    "options exit",
+    "game uncovered"
+  ]);
+  assert_eq!(send(&mut headless, Command::Pop), ["game event", "game exit", "menu uncovered"]);
+  assert!(headless.is_running());
+
+  assert_eq!(send(&mut headless, Command::Pop), ["menu event", "menu exit"]);
+  assert!(headless.has_exited());
+}
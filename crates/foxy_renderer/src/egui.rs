@@ -1,20 +1,14 @@
-use std::sync::Arc;
-
-use egui::{epaint::Shadow, Context, FullOutput, RawInput, Rounding, Visuals};
+use egui::{Context, FullOutput};
 use egui_wgpu::{Renderer, ScreenDescriptor};
-use egui_winit::State;
 use wgpu::{CommandEncoder, Device, Queue, TextureFormat, TextureView};
-use winit::{event::WindowEvent, window::Window};
 
 pub struct EguiRenderer {
-  window: Arc<Window>,
   context: Context,
   renderer: Renderer,
 }
 
 impl EguiRenderer {
   pub fn new(
-    window: Arc<Window>,
     device: &Device,
     egui_context: Context,
     output_color_format: TextureFormat,
@@ -25,7 +19,6 @@ impl EguiRenderer {
     let egui_renderer = Renderer::new(device, output_color_format, output_depth_format, msaa_samples);
 
     EguiRenderer {
-      window,
       context: egui_context,
       renderer: egui_renderer,
     }
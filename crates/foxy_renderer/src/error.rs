@@ -4,8 +4,10 @@ use thiserror::Error;
 pub enum RendererError {
   #[error("{0}")]
   Error(String),
+  /// A [`wgpu::Error`], kept as its message since the error itself isn't
+  /// `Sync`.
   #[error("{0}")]
-  WgpuError(#[from] wgpu::Error),
+  WgpuError(String),
   #[error("{0}")]
   SurfaceError(#[from] wgpu::SurfaceError),
   #[error("must rebuild swapchain")]
@@ -16,6 +18,12 @@ pub enum RendererError {
   RequestDeviceError(#[from] wgpu::RequestDeviceError),
 }
 
+impl From<wgpu::Error> for RendererError {
+  fn from(error: wgpu::Error) -> Self {
+    RendererError::WgpuError(error.to_string())
+  }
+}
+
 #[macro_export]
 macro_rules! renderer_error {
   () => {
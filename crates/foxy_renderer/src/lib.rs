@@ -1,5 +1,4 @@
 #![deny(unsafe_op_in_unsafe_fn)]
-#![feature(associated_type_defaults)]
 #![warn(clippy::indexing_slicing)]
 
 pub mod error;
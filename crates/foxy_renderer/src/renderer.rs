@@ -1,25 +1,23 @@
 use std::sync::Arc;
 
-use egui::{Context, FullOutput, RawInput};
+use egui::Context;
 use egui_wgpu::ScreenDescriptor;
 use foxy_utils::time::Time;
-use image::{DynamicImage, GenericImageView};
-use tracing::debug;
-use wgpu::{Color, TextureFormat};
-use winit::{event::WindowEvent, window::Window};
+use wgpu::Color;
+use winit::window::Window;
 
 use self::{
   context::GraphicsContext,
   material::StandardMaterial,
   mesh::Mesh,
-  render_data::{Drawable, RenderData},
+  render_data::RenderData,
   render_pass::{simple::SimplePass, tonemap::ToneMapPass, Pass},
   target::RenderTarget,
 };
 use crate::{
   egui::EguiRenderer,
   error::RendererError,
-  renderer::{material::Material, texture::DiffuseTexture, vertex::Vertex},
+  renderer::{texture::DiffuseTexture, vertex::Vertex},
 };
 
 pub mod context;
@@ -40,8 +38,6 @@ pub struct Renderer {
   simple_pass: SimplePass,
   tone_map_pass: ToneMapPass,
 
-  textured_material: Arc<StandardMaterial>,
-  standard_material: Arc<StandardMaterial>,
   mesh: Mesh,
 
   is_dirty: bool,
@@ -55,17 +51,10 @@ impl Renderer {
     a: 1.0,
   };
 
-  pub fn new(window: Arc<Window>, egui_context: Context) -> Result<Self, RendererError> {
+  pub fn new(window: Arc<Window>, egui_context: Context, vsync: bool) -> Result<Self, RendererError> {
     pollster::block_on(async {
-      let context = GraphicsContext::new(window.clone())?;
-      let egui = EguiRenderer::new(
-        window.clone(),
-        context.device(),
-        egui_context,
-        GraphicsContext::SURFACE_FORMAT,
-        None,
-        1,
-      );
+      let context = GraphicsContext::new(window.clone(), vsync)?;
+      let egui = EguiRenderer::new(context.device(), egui_context, GraphicsContext::SURFACE_FORMAT, None, 1);
 
       let render_target = RenderTarget::new(window.clone(), context.device());
 
@@ -79,7 +68,6 @@ impl Renderer {
       );
 
       let textured_material = StandardMaterial::new(context.device(), context.queue(), Some(diffuse_texture));
-      let standard_material = StandardMaterial::new(context.device(), context.queue(), None);
 
       let mesh = Mesh::new(
         context.device(),
@@ -88,29 +76,25 @@ impl Renderer {
             position: [-0.5, -0.5, 0.0],
             color: [1.0, 0.0, 0.0, 1.0],
             uv: [0., 1.],
-            ..Default::default()
           },
           Vertex {
             position: [0.5, -0.5, 0.0],
             color: [1.0, 0.0, 0.0, 1.0],
             uv: [1., 1.],
-            ..Default::default()
           },
           Vertex {
             position: [0.5, 0.5, 0.0],
             color: [0.0, 1.0, 0.0, 1.0],
             uv: [1., 0.],
-            ..Default::default()
           },
           Vertex {
             position: [-0.5, 0.5, 0.0],
             color: [0.0, 0.0, 1.0, 1.0],
             uv: [0., 0.],
-            ..Default::default()
           },
         ],
         Some(&[0, 1, 2, 0, 2, 3]),
-        textured_material.clone(),
+        textured_material,
       );
 
       Ok(Self {
@@ -120,8 +104,6 @@ impl Renderer {
         render_target,
         simple_pass,
         tone_map_pass,
-        textured_material,
-        standard_material,
         mesh,
         is_dirty: false,
       })
@@ -136,7 +118,22 @@ impl Renderer {
     self.is_dirty = true;
   }
 
-  pub fn draw(&mut self, render_time: Time, render_data: RenderData) -> Result<(), RendererError> {
+  /// Rebuilds the surface and everything sized to it, for when the app comes
+  /// back from being suspended.
+  pub fn recreate_surface(&mut self) -> Result<(), RendererError> {
+    self.context.recreate_surface()?;
+    self.refresh();
+    Ok(())
+  }
+
+  /// Skips frames while the window has no area, e.g. when minimized, since
+  /// surfaces can't be zero-sized.
+  pub fn draw(&mut self, _render_time: Time, render_data: RenderData) -> Result<(), RendererError> {
+    let size = self.window.inner_size();
+    if size.width == 0 || size.height == 0 {
+      return Ok(());
+    }
+
     match self.next_frame() {
       Ok(frame) => {
         let view = frame.texture.create_view(&wgpu::TextureViewDescriptor::default());
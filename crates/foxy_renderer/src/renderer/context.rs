@@ -8,6 +8,7 @@ use crate::error::RendererError;
 
 pub struct GraphicsContext {
   window: Arc<Window>,
+  instance: wgpu::Instance,
   surface: wgpu::Surface<'static>,
   config: wgpu::SurfaceConfiguration,
   device: wgpu::Device,
@@ -17,7 +18,9 @@ pub struct GraphicsContext {
 impl GraphicsContext {
   pub const SURFACE_FORMAT: TextureFormat = TextureFormat::Rgba8UnormSrgb;
 
-  pub fn new(window: Arc<Window>) -> Result<Self, RendererError> {
+  /// With `vsync` off, frames are presented as soon as they're ready, which
+  /// can tear.
+  pub fn new(window: Arc<Window>, vsync: bool) -> Result<Self, RendererError> {
     pollster::block_on(async {
       let size = window.inner_size();
 
@@ -62,7 +65,11 @@ impl GraphicsContext {
         format: surface_format,
         width: size.width,
         height: size.height,
-        present_mode: wgpu::PresentMode::AutoNoVsync,
+        present_mode: if vsync {
+          wgpu::PresentMode::AutoVsync
+        } else {
+          wgpu::PresentMode::AutoNoVsync
+        },
         alpha_mode: *surface_caps.alpha_modes.first().unwrap(),
         view_formats: vec![],
         desired_maximum_frame_latency: 2,
@@ -72,6 +79,7 @@ impl GraphicsContext {
 
       Ok(Self {
         window,
+        instance,
         surface,
         config,
         device,
@@ -92,6 +100,14 @@ impl GraphicsContext {
     &self.surface
   }
 
+  /// Replaces a surface whose native window went away, e.g. after the app was
+  /// suspended on Android.
+  pub fn recreate_surface(&mut self) -> Result<(), RendererError> {
+    self.surface = self.instance.create_surface(self.window.clone())?;
+    self.reconfigure();
+    Ok(())
+  }
+
   pub fn reconfigure(&mut self) {
     let new_size = self.window.inner_size();
     self.config.width = new_size.width.max(1);
@@ -1,8 +1,8 @@
 use std::sync::Arc;
 
-use wgpu::{include_wgsl, Device, Queue, TextureFormat};
+use wgpu::{Device, Queue, TextureFormat};
 
-use super::{context::GraphicsContext, texture::DiffuseTexture, vertex::Vertex, Renderer};
+use super::{context::GraphicsContext, texture::DiffuseTexture};
 
 #[repr(C)]
 pub struct MaterialUniforms {
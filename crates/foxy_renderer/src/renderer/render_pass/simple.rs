@@ -1,8 +1,8 @@
-use wgpu::{Color, CommandEncoder};
+use wgpu::CommandEncoder;
 
 use super::{create_render_pipeline, Pass};
 use crate::renderer::{
-  context::GraphicsContext, mesh::Mesh, render_data::Drawable, target::RenderTarget, texture::DiffuseTexture, vertex::Vertex, Renderer
+  mesh::Mesh, render_data::Drawable, target::RenderTarget, texture::DiffuseTexture, vertex::Vertex
 };
 
 pub struct SimplePass {
@@ -62,5 +62,5 @@ impl Pass for SimplePass {
     Ok(())
   }
 
-  fn resize(&mut self, device: &wgpu::Device, render_target: &RenderTarget) {}
+  fn resize(&mut self, _device: &wgpu::Device, _render_target: &RenderTarget) {}
 }
@@ -1,7 +1,7 @@
-use wgpu::{Color, CommandEncoder};
+use wgpu::CommandEncoder;
 
 use super::{create_render_pipeline, Pass};
-use crate::renderer::{context::GraphicsContext, render_data::Drawable, target::RenderTarget, Renderer};
+use crate::renderer::{target::RenderTarget, Renderer};
 
 pub struct ToneMapPass {
   pipeline: wgpu::RenderPipeline,
@@ -80,7 +80,7 @@ impl Pass for ToneMapPass {
     &mut self,
     command_encoder: &mut CommandEncoder,
     render_target: &wgpu::TextureView,
-    mesh: &crate::renderer::mesh::Mesh,
+    _mesh: &crate::renderer::mesh::Mesh,
   ) -> Result<(), crate::error::RendererError> {
     let mut render_pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
       label: Some("HDR Pass"),
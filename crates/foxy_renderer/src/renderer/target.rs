@@ -3,8 +3,6 @@ use std::sync::Arc;
 use wgpu::{Device, TextureFormat};
 use winit::window::Window;
 
-use super::Renderer;
-
 pub struct RenderTarget {
   window: Arc<Window>,
   pub texture: wgpu::Texture,
@@ -19,8 +17,8 @@ impl RenderTarget {
     let texture = device.create_texture(&wgpu::TextureDescriptor {
       label: Some("HDR Render Texture"),
       size: wgpu::Extent3d {
-        width: window.inner_size().width,
-        height: window.inner_size().height,
+        width: window.inner_size().width.max(1),
+        height: window.inner_size().height.max(1),
         depth_or_array_layers: 1,
       },
       mip_level_count: 1,
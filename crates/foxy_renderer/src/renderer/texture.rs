@@ -1,15 +1,9 @@
-use std::{
-  cell::OnceCell,
-  mem::size_of,
-  sync::{Arc, OnceLock},
-};
+use std::{mem::size_of, sync::OnceLock};
 
-use image::{EncodableLayout, GenericImageView, Pixel};
-use itertools::Itertools;
-use wgpu::{Device, Extent3d, Queue, Texture};
-use winit::window::Window;
+use image::{EncodableLayout, GenericImageView};
+use wgpu::{Device, Queue, Texture};
 
-use super::target::RenderTarget;
+use crate::{error::RendererError, renderer_error};
 
 pub struct DiffuseTexture {
   pub texture: Texture,
@@ -19,29 +13,43 @@ pub struct DiffuseTexture {
 }
 
 impl DiffuseTexture {
+  /// Decodes an encoded image, e.g. the contents of a PNG file.
   pub fn new(device: &Device, queue: &Queue, bytes: &[u8]) -> Self {
     let diffuse_image = image::load_from_memory(bytes).unwrap();
-    let dimensions = diffuse_image.dimensions();
+    let (width, height) = diffuse_image.dimensions();
     let data = diffuse_image.to_rgba8();
 
+    Self::from_rgba(device, queue, width, height, data.as_bytes()).unwrap()
+  }
+
+  /// Uploads raw pixels, four bytes per pixel in RGBA order with rows top to
+  /// bottom and no padding between them. Fails if `rgba` is the wrong size
+  /// for a `width` by `height` image.
+  pub fn from_rgba(
+    device: &Device,
+    queue: &Queue,
+    width: u32,
+    height: u32,
+    rgba: &[u8],
+  ) -> Result<Self, RendererError> {
     let size = wgpu::Extent3d {
-      width: dimensions.0,
-      height: dimensions.1,
+      width,
+      height,
       depth_or_array_layers: 1,
     };
 
     let unpadded_bytes_per_row = size_of::<[u8; 4]>() * size.width as usize;
+    if rgba.len() != unpadded_bytes_per_row * size.height as usize {
+      return Err(renderer_error!(
+        "{} bytes of rgba don't make a {width}x{height} image",
+        rgba.len()
+      ));
+    }
+
     let padding = (256 - (unpadded_bytes_per_row % 256)) % 256;
     let mut padded_data = Vec::with_capacity((unpadded_bytes_per_row + padding) * size.height as usize);
-    for (_, row) in data.enumerate_rows() {
-      padded_data.extend_from_slice(
-        &row
-          .flat_map(|(_, _, value)| {
-            let bytes = value.channels();
-            bytes.as_bytes().to_vec()
-          })
-          .collect_vec(),
-      );
+    for row in rgba.chunks_exact(unpadded_bytes_per_row.max(1)) {
+      padded_data.extend_from_slice(row);
       padded_data.resize(padded_data.len() + padding, 0);
     }
 
@@ -95,12 +103,12 @@ impl DiffuseTexture {
       ],
     });
 
-    Self {
+    Ok(Self {
       texture,
       view,
       sampler,
       bind_group,
-    }
+    })
   }
 
   pub fn bind_group_layout(device: &Device) -> &wgpu::BindGroupLayout {
@@ -33,13 +33,19 @@ impl<SenderMessage: Send + Sync, ReceiverMessage: Send + Sync> Mailbox<SenderMes
     self.receiver.try_recv().map_err(MessagingError::from)
   }
 
+  pub fn recv_deadline(&self, deadline: Instant) -> Result<ReceiverMessage, MessagingError<SenderMessage>> {
+    self.receiver.recv_deadline(deadline).map_err(MessagingError::from)
+  }
+
   pub fn send_and_recv(&self, message: SenderMessage) -> Result<ReceiverMessage, MessagingError<SenderMessage>> {
     self.send(message)?;
     self.recv()
   }
 }
 
-use crossbeam::channel::{Receiver, RecvError, SendError, Sender, TryRecvError};
+use std::time::Instant;
+
+use crossbeam::channel::{Receiver, RecvError, RecvTimeoutError, SendError, Sender, TryRecvError};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -59,4 +65,9 @@ pub enum MessagingError<SenderMessage> {
     #[from]
     error: RecvError,
   },
+  #[error("{error}")]
+  RecvTimeoutError {
+    #[from]
+    error: RecvTimeoutError,
+  },
 }
@@ -1,27 +1,29 @@
 pub mod game_loop;
+pub mod pacer;
 pub mod stopwatch;
 pub mod timer;
 
 use std::time::Duration;
 
-use quanta::Instant;
+use quanta::{Clock, Instant};
 use thiserror::Error;
-use tracing::*;
 
 use crate::types::ring_buffer::RingBuffer;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct Time {
+  clock: Clock,
   start_time: Instant,
   delta_time: Duration,
   tick_delta_time: Duration,
   average_delta_time: Duration,
+  paused_at: Option<Instant>,
 }
 
 #[allow(unused)]
 impl Time {
   pub fn since_start(&self) -> Duration {
-    Instant::now() - self.start_time
+    self.paused_at.unwrap_or_else(|| self.clock.now()) - self.start_time
   }
 
   pub fn delta(&self) -> &Duration {
@@ -49,11 +51,11 @@ impl Time {
   }
 
   pub fn now(&self) -> Instant {
-    Instant::now()
+    self.clock.now()
   }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TimeCreateInfo {
   pub tick_rate: f64,
   pub bail_threshold: u32,
@@ -74,9 +76,16 @@ impl TimeCreateInfo {
   pub fn build(&self) -> EngineTime {
     EngineTime::new(self.tick_rate, self.bail_threshold, self.max_samples)
   }
+
+  /// Builds an [`EngineTime`] that reads from `clock`, e.g. one made with
+  /// [`Clock::mock`] to advance time by hand.
+  pub fn build_with_clock(&self, clock: Clock) -> EngineTime {
+    self.build().with_clock(clock)
+  }
 }
 
 pub struct EngineTime {
+  clock: Clock,
   tick_rate: f64,
   tick_time: Duration,
   lag_time: Duration,
@@ -94,6 +103,8 @@ pub struct EngineTime {
   tick_delta_time: Duration,
 
   frame_times: RingBuffer<Duration>,
+
+  paused_at: Option<Instant>,
 }
 
 impl Default for EngineTime {
@@ -101,20 +112,24 @@ impl Default for EngineTime {
     const TICK_RATE: f64 = 128.0;
     let tick_time: Duration = Duration::from_secs_f64(1. / TICK_RATE);
     const BAIL_THRESHOLD: u32 = 1024;
+    let clock = Clock::new();
+    let now = clock.now();
     Self {
+      clock,
       tick_rate: TICK_RATE,
       tick_time,
       lag_time: Default::default(),
       step_count: 0,
       bail_threshold: BAIL_THRESHOLD,
-      start_time: Instant::now(),
-      previous_frame: Instant::now(),
-      current_frame: Instant::now(),
+      start_time: now,
+      previous_frame: now,
+      current_frame: now,
       delta_time: Default::default(),
-      tick_previous_frame: Instant::now(),
-      tick_current_frame: Instant::now(),
+      tick_previous_frame: now,
+      tick_current_frame: now,
       tick_delta_time: Default::default(),
       frame_times: RingBuffer::new(100),
+      paused_at: None,
     }
   }
 }
@@ -139,12 +154,48 @@ impl EngineTime {
     self
   }
 
+  /// Swaps the time source and restarts all timestamps from it.
+  pub fn with_clock(mut self, clock: Clock) -> Self {
+    let now = clock.now();
+    self.clock = clock;
+    self.start_time = now;
+    self.previous_frame = now;
+    self.current_frame = now;
+    self.tick_previous_frame = now;
+    self.tick_current_frame = now;
+    self
+  }
+
+  pub fn clock(&self) -> &Clock {
+    &self.clock
+  }
+
+  /// Starts over from now, as if just built: time since start, deltas,
+  /// pending fixed ticks and frame time samples are cleared. The clock and
+  /// settings stay, and a paused clock stays paused.
+  pub fn reset(&mut self) {
+    let now = self.clock.now();
+    self.start_time = now;
+    self.previous_frame = now;
+    self.current_frame = now;
+    self.tick_previous_frame = now;
+    self.tick_current_frame = now;
+    self.lag_time = Duration::ZERO;
+    self.step_count = 0;
+    self.delta_time = Duration::ZERO;
+    self.tick_delta_time = Duration::ZERO;
+    self.frame_times = RingBuffer::new(self.frame_times.capacity());
+    self.paused_at = self.paused_at.map(|_| now);
+  }
+
   pub fn time(&self) -> Time {
     Time {
+      clock: self.clock.clone(),
       start_time: self.start_time,
       delta_time: self.delta_time,
       tick_delta_time: self.tick_delta_time,
       average_delta_time: self.average_delta(),
+      paused_at: self.paused_at,
     }
   }
 
@@ -166,7 +217,7 @@ impl EngineTime {
   }
 
   pub fn update(&mut self) {
-    self.current_frame = Instant::now();
+    self.current_frame = self.now();
     self.delta_time = self.current_frame - self.previous_frame;
     self.previous_frame = self.current_frame;
     self.lag_time += self.delta_time;
@@ -175,8 +226,44 @@ impl EngineTime {
     self.frame_times.push(self.delta_time);
   }
 
+  /// Stops time from passing until [`resume`](Self::resume), both for
+  /// [`Time::since_start`] and for delta and fixed tick timing.
+  pub fn pause(&mut self) {
+    if self.paused_at.is_none() {
+      self.paused_at = Some(self.clock.now());
+    }
+  }
+
+  pub fn resume(&mut self) {
+    if let Some(paused_at) = self.paused_at.take() {
+      let paused_for = self.clock.now() - paused_at;
+      self.start_time += paused_for;
+      self.previous_frame += paused_for;
+      self.tick_previous_frame += paused_for;
+    }
+  }
+
+  pub fn is_paused(&self) -> bool {
+    self.paused_at.is_some()
+  }
+
+  /// The clock's time, held at the moment of pausing while paused.
+  fn now(&self) -> Instant {
+    self.paused_at.unwrap_or_else(|| self.clock.now())
+  }
+
+  /// Counts at most `max` of the time since the last update, e.g. after the
+  /// game thread slept waiting for input. Otherwise a long idle stretch would
+  /// show up as one huge delta and a burst of fixed ticks.
+  pub fn clamp_idle(&mut self, max: Duration) {
+    let earliest = self.clock.now().checked_sub(max);
+    if let Some(earliest) = earliest.filter(|earliest| self.previous_frame < *earliest) {
+      self.previous_frame = earliest;
+    }
+  }
+
   pub fn tick(&mut self) {
-    self.tick_current_frame = Instant::now();
+    self.tick_current_frame = self.now();
     self.tick_delta_time = self.tick_current_frame - self.tick_previous_frame;
     self.tick_previous_frame = self.tick_current_frame;
     self.lag_time -= self.tick_time;
@@ -205,3 +292,52 @@ pub enum TimeError {
   #[error("struggling to catch up with tick rate")]
   TickOverflow,
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const MS: Duration = Duration::from_millis(1);
+
+  #[test]
+  fn paused_time_stands_still() {
+    let (clock, mock) = Clock::mock();
+    let mut time = TimeCreateInfo::default().build_with_clock(clock);
+
+    mock.increment(10 * MS);
+    time.pause();
+    mock.increment(500 * MS);
+    time.update();
+    assert_eq!(*time.time().delta(), 10 * MS);
+    assert_eq!(time.time().since_start(), 10 * MS);
+
+    mock.increment(500 * MS);
+    time.update();
+    assert_eq!(*time.time().delta(), Duration::ZERO);
+    assert_eq!(time.time().since_start(), 10 * MS);
+
+    time.resume();
+    mock.increment(20 * MS);
+    time.update();
+    assert_eq!(*time.time().delta(), 20 * MS);
+    assert_eq!(time.time().since_start(), 30 * MS);
+  }
+
+  #[test]
+  fn pausing_holds_back_fixed_ticks() {
+    let (clock, mock) = Clock::mock();
+    let mut time = TimeCreateInfo::default().build_with_clock(clock);
+
+    time.pause();
+    mock.increment(Duration::from_secs(1));
+    time.update();
+    assert!(!time.should_do_tick_unchecked());
+
+    time.resume();
+    mock.increment(time.tick_time);
+    time.update();
+    assert!(time.should_do_tick_unchecked());
+    time.tick();
+    assert!(!time.should_do_tick_unchecked());
+  }
+}
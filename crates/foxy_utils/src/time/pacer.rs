@@ -0,0 +1,115 @@
+use std::time::{Duration, Instant};
+
+/// Spaces frames out evenly at a fixed rate.
+///
+/// OS sleeps can overshoot by a millisecond or more, so the pacer sleeps until
+/// just before the deadline and spins through the rest. Frames that run late
+/// push the schedule back instead of being made up with a burst of short
+/// frames.
+#[derive(Debug, Clone)]
+pub struct FramePacer {
+  period: Duration,
+  next_frame: Instant,
+}
+
+/// What a [`FramePacer`] wants the caller to do next.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Pace {
+  /// Run the frame now.
+  Ready,
+  /// Too early. Sleep until the given instant and ask again.
+  WaitUntil(Instant),
+}
+
+impl FramePacer {
+  /// How long before a deadline to stop sleeping and start spinning.
+  pub const SPIN_MARGIN: Duration = Duration::from_millis(2);
+
+  /// `rate` in frames per second. Rates below one are treated as one.
+  pub fn new(rate: f64) -> Self {
+    Self {
+      period: Duration::from_secs_f64(1.0 / rate.max(1.0)),
+      next_frame: Instant::now(),
+    }
+  }
+
+  pub fn period(&self) -> Duration {
+    self.period
+  }
+
+  /// When the next frame is due.
+  pub fn next_frame(&self) -> Instant {
+    self.next_frame
+  }
+
+  /// When to stop sleeping and start spinning for the next frame.
+  pub fn wake_time(&self) -> Instant {
+    self
+      .next_frame
+      .checked_sub(Self::SPIN_MARGIN)
+      .unwrap_or(self.next_frame)
+  }
+
+  /// Non-blocking, for event loops that do their own sleeping. Spins when the
+  /// deadline is closer than [`SPIN_MARGIN`](Self::SPIN_MARGIN), since waking
+  /// again that soon isn't reliable.
+  pub fn poll(&mut self) -> Pace {
+    if Instant::now() < self.wake_time() {
+      return Pace::WaitUntil(self.wake_time());
+    }
+
+    spin_until(self.next_frame);
+    self.advance();
+    Pace::Ready
+  }
+
+  /// Blocks until the next frame is due.
+  pub fn wait(&mut self) {
+    while let Pace::WaitUntil(wake) = self.poll() {
+      std::thread::sleep(wake.saturating_duration_since(Instant::now()));
+    }
+  }
+
+  fn advance(&mut self) {
+    let now = Instant::now();
+    self.next_frame += self.period;
+    if self.next_frame < now {
+      self.next_frame = now + self.period;
+    }
+  }
+}
+
+fn spin_until(deadline: Instant) {
+  while Instant::now() < deadline {
+    std::hint::spin_loop();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn rates_below_one_are_clamped() {
+    assert_eq!(FramePacer::new(0.5).period(), Duration::from_secs(1));
+    assert_eq!(FramePacer::new(0.0).period(), Duration::from_secs(1));
+    assert_eq!(FramePacer::new(-60.0).period(), Duration::from_secs(1));
+    assert_eq!(FramePacer::new(4.0).period(), Duration::from_millis(250));
+  }
+
+  #[test]
+  fn late_frames_push_the_schedule_back() {
+    let mut pacer = FramePacer::new(100.0);
+    assert_eq!(pacer.poll(), Pace::Ready);
+    let on_time = pacer.next_frame();
+
+    std::thread::sleep(pacer.period() * 5);
+    assert_eq!(pacer.poll(), Pace::Ready);
+    let rescheduled = pacer.next_frame();
+    assert!(rescheduled >= on_time + pacer.period() * 4);
+
+    // The missed frames aren't made up
+    assert_eq!(pacer.poll(), Pace::WaitUntil(pacer.wake_time()));
+    assert_eq!(pacer.next_frame(), rescheduled);
+  }
+}
@@ -1 +1,7 @@
-pub use crate::time::{stopwatch::Stopwatch, timer::Timer, EngineTime, Time};
+pub use crate::time::{
+  pacer::{FramePacer, Pace},
+  stopwatch::Stopwatch,
+  timer::Timer,
+  EngineTime,
+  Time,
+};
@@ -34,11 +34,11 @@ impl<T> RingBuffer<T> {
     self.capacity
   }
 
-  pub fn iter(&self) -> vec_deque::Iter<T> {
+  pub fn iter(&self) -> vec_deque::Iter<'_, T> {
     self.items.iter()
   }
 
-  pub fn iter_mut(&mut self) -> vec_deque::IterMut<T> {
+  pub fn iter_mut(&mut self) -> vec_deque::IterMut<'_, T> {
     self.items.iter_mut()
   }
 }
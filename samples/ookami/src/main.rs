@@ -1,7 +1,10 @@
 #![cfg_attr(all(windows, not(debug_assertions)), windows_subsystem = "windows")]
 
-use foxy::{egui::{self, Align2}, prelude::*};
-use tracing::{debug, warn};
+use foxy::{
+  egui::{self, Align2},
+  prelude::*,
+};
+use tracing::debug;
 
 pub struct App {
   x: u32,
@@ -19,7 +22,7 @@ impl Runnable for App {
     Self { x: 0 }
   }
 
-  fn input(&mut self, foxy: &Foxy, event: &InputEvent) {
+  fn input(&mut self, foxy: &Foxy, event: &InputEvent) -> FoxyResult<()> {
     if let InputEvent::Mouse(button, state) = event {
       debug!(
         "UPDATE | {:?}: {:?} + {:?}",
@@ -28,9 +31,11 @@ impl Runnable for App {
         foxy.read().input().shift().is_pressed()
       )
     }
+
+    Ok(())
   }
 
-  fn gui(&mut self, foxy: &Foxy, egui: &foxy::egui::Context) {
+  fn gui(&mut self, _foxy: &Foxy, egui: &foxy::egui::Context) -> FoxyResult<()> {
     egui::Window::new("Settings")
       .default_open(false)
       .default_size((50.0, 50.0))
@@ -46,6 +51,8 @@ impl Runnable for App {
           debug!("x: {}", self.x);
         }
       });
+
+    Ok(())
   }
 }
 
@@ -16,10 +16,14 @@ impl Runnable for App {
     Self {}
   }
 
-  fn update(&mut self, _foxy: &Foxy, event: &FoxyEvent) {
-    if let FoxyEvent::Input(InputEvent::Keyboard(..)) = event {
-      debug!("UPDATE: {:?}", event)
+  fn update(&mut self, _foxy: &Foxy, events: &[FoxyEvent]) -> FoxyResult<()> {
+    for event in events {
+      if let FoxyEvent::Input(InputEvent::Keyboard(..)) = event {
+        debug!("UPDATE: {:?}", event)
+      }
     }
+
+    Ok(())
   }
 }
 